@@ -0,0 +1,111 @@
+//! Compares counting scratchcard matches with a fresh `HashSet` and boxed
+//! slice per card (how problem 4 used to work) against a `NumSet` of winning
+//! numbers (how it works now), on cards with puzzle sized numbers and on cards
+//! with numbers too big for the bitset.
+//!
+//! Run with `cargo bench --bench num_set`. Set `AOC_BENCH_CARDS` to change how
+//! many cards are generated, which defaults to 1000000.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use aoc_rust_2023::helpers::num_set::NumSet;
+use aoc_rust_2023::{memory, AocResult};
+
+const ITERATIONS: u32 = 3;
+
+/// Cards shaped like the puzzle's: 10 winning numbers and 25 of mine, all
+/// below `max`.
+fn generate_cards(count: usize, max: u64) -> Vec<(String, String)> {
+    let mut state: u64 = 0x2023_1204;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    // Like the puzzle, no number is repeated within a list.
+    let mut nums = |len: usize| -> String {
+        let mut picked: Vec<u64> = Vec::with_capacity(len);
+        while picked.len() < len {
+            let num = 1 + next() % (max - 1);
+            if !picked.contains(&num) {
+                picked.push(num);
+            }
+        }
+        picked
+            .iter()
+            .map(|num| format!("{num:>2}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    (0..count).map(|_| (nums(10), nums(25))).collect()
+}
+
+fn parse<T: FromIterator<u32>>(nums: &str) -> AocResult<T> {
+    nums.split(' ')
+        .filter(|num| !num.is_empty())
+        .map(|num| Ok(num.parse()?))
+        .collect()
+}
+
+fn by_hash_set(cards: &[(String, String)]) -> AocResult<usize> {
+    let mut total = 0;
+    for (winning, mine) in cards {
+        let winning: HashSet<u32> = parse(winning)?;
+        let mine: Vec<u32> = parse(mine)?;
+        let mine = mine.into_boxed_slice();
+        total += mine.iter().filter(|num| winning.contains(num)).count();
+    }
+    Ok(total)
+}
+
+fn by_num_set(cards: &[(String, String)]) -> AocResult<usize> {
+    let mut total = 0;
+    for (winning, mine) in cards {
+        let winning: NumSet = parse(winning)?;
+        let mine: Vec<u32> = parse(mine)?;
+        total += mine.iter().filter(|&&num| winning.contains(num)).count();
+    }
+    Ok(total)
+}
+
+fn bench(
+    name: &str,
+    cards: &[(String, String)],
+    count: fn(&[(String, String)]) -> AocResult<usize>,
+) -> AocResult<usize> {
+    let mut best = Duration::MAX;
+    let mut answer = None;
+    let mut usage = None;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let (matches, run_usage) = memory::measure(|| count(cards));
+        best = best.min(start.elapsed());
+        answer = Some(matches?);
+        usage = Some(run_usage);
+    }
+    let answer = answer.unwrap();
+    print!("{name:<9} {answer} matches in {best:?} (best of {ITERATIONS})");
+    if memory::ENABLED {
+        print!(", {}", usage.unwrap());
+    }
+    println!();
+    Ok(answer)
+}
+
+fn main() -> AocResult<()> {
+    let count = std::env::var("AOC_BENCH_CARDS")
+        .ok()
+        .map(|count| count.parse())
+        .transpose()?
+        .unwrap_or(1_000_000);
+    for (label, max) in [("numbers below 100", 100), ("numbers below 10000", 10_000)] {
+        let cards = generate_cards(count, max);
+        println!("{count} cards with {label}");
+        let hashed = bench("HashSet", &cards, by_hash_set)?;
+        let bits = bench("NumSet", &cards, by_num_set)?;
+        anyhow::ensure!(hashed == bits, "The two versions disagree");
+    }
+    Ok(())
+}
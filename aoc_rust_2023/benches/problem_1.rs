@@ -0,0 +1,183 @@
+//! Compares problem 1 part 2 reading a large generated input line by line into
+//! `String`s and `Vec<char>`s (how it used to work) against the byte scanner.
+//!
+//! Run with `cargo bench --bench problem_1`. Set `AOC_BENCH_MB` to change the
+//! size of the input, which defaults to 64 MB.
+
+use std::io::{BufRead, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use aoc_rust_2023::config::Params;
+use aoc_rust_2023::helpers;
+use aoc_rust_2023::impls::problem_1;
+use aoc_rust_2023::{memory, AocResult, Part, Solution};
+
+const ITERATIONS: u32 = 3;
+const WORDS: [(&str, u32); 19] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Letters no number word uses, so they never make or break a match.
+const FILLER: &[u8; 12] = b"abcdjklmpqyz";
+
+/// Writes random calibration lines until the file is `megabytes` big. Every
+/// line has at least one digit so it is always solvable.
+///
+/// The old solver only matches lowercase words but also matches them spelled
+/// backwards, so "owt" is a 2. To give both solvers the same answers, words
+/// are always followed by filler and random letters are filler, in either case.
+fn write_stress_input(path: &std::path::Path, megabytes: usize) -> AocResult<()> {
+    let mut out = BufWriter::new(std::fs::File::create(path)?);
+    let mut state: u64 = 0x2023_1201;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut written = 0;
+    while written < megabytes << 20 {
+        let mut line = String::new();
+        line.push((b'0' + (next() % 10) as u8) as char);
+        for _ in 0..next() % 12 {
+            let filler = FILLER[(next() % 12) as usize];
+            match next() % 3 {
+                0 => {
+                    line.push_str(WORDS[10 + (next() % 9) as usize].0);
+                    line.push(filler as char);
+                }
+                1 => line.push(filler as char),
+                _ => line.push(filler.to_ascii_uppercase() as char),
+            }
+        }
+        writeln!(out, "{line}")?;
+        written += line.len() + 1;
+    }
+    Ok(())
+}
+
+/// Problem 1 part 2 as it was before any of the dictionary work, with two
+/// `Vec<char>` per line. Kept as written apart from `Solution::Int` now taking
+/// an `i64`.
+mod chars {
+    use aoc_rust_2023::{AocResult, Solution};
+
+    fn find_number_or_written_out_number(
+        line_chars: impl Iterator<Item = char>,
+        mini_string_maker: &[char],
+    ) -> AocResult<u32> {
+        for (index, maybe_digit) in line_chars.enumerate() {
+            if let Some(digit) = maybe_digit.to_digit(10) {
+                return Ok(digit);
+            }
+            let mini_str =
+                &mini_string_maker[index..std::cmp::min(index + 5, mini_string_maker.len())];
+            match mini_str {
+                ['t', 'h', 'r', 'e', 'e'] | ['e', 'e', 'r', 'h', 't'] => return Ok(3),
+                ['s', 'e', 'v', 'e', 'n'] | ['n', 'e', 'v', 'e', 's'] => return Ok(7),
+                ['e', 'i', 'g', 'h', 't'] | ['t', 'h', 'g', 'i', 'e'] => return Ok(8),
+                _ => (),
+            };
+            let mini_str = &mini_str[0..std::cmp::min(4, mini_str.len())];
+            match mini_str {
+                ['f', 'o', 'u', 'r'] | ['r', 'u', 'o', 'f'] => return Ok(4),
+                ['f', 'i', 'v', 'e'] | ['e', 'v', 'i', 'f'] => return Ok(5),
+                ['n', 'i', 'n', 'e'] | ['e', 'n', 'i', 'n'] => return Ok(9),
+                _ => (),
+            };
+            let mini_str = &mini_str[0..std::cmp::min(3, mini_str.len())];
+            match mini_str {
+                ['o', 'n', 'e'] | ['e', 'n', 'o'] => return Ok(1),
+                ['t', 'w', 'o'] | ['o', 'w', 't'] => return Ok(2),
+                ['s', 'i', 'x'] | ['x', 'i', 's'] => return Ok(6),
+                _ => (),
+            }
+        }
+        anyhow::bail!("The AOC input is broken?!?!?!?!?!?!?");
+    }
+
+    pub fn solve_p2_part_2(
+        lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    ) -> AocResult<Solution> {
+        let mut sum = 0;
+        for line in lines {
+            let line = line?;
+            let char_array: Vec<char> = line.chars().collect();
+            let reversed_char_array: Vec<char> = line.chars().rev().collect();
+            let first_digit = find_number_or_written_out_number(line.trim().chars(), &char_array)?;
+            let second_digit =
+                find_number_or_written_out_number(line.trim().chars().rev(), &reversed_char_array)?;
+            sum += first_digit * 10 + second_digit;
+        }
+        Ok(Solution::Int(sum as i64))
+    }
+}
+
+fn bench(
+    name: &str,
+    path: &std::path::Path,
+    solve: impl Fn(std::io::BufReader<std::fs::File>) -> AocResult<Solution>,
+) -> AocResult<Solution> {
+    let mut best = Duration::MAX;
+    let mut answer = None;
+    let mut usage = None;
+    for _ in 0..ITERATIONS {
+        let input = helpers::open_input(path)?;
+        let start = Instant::now();
+        let (solution, run_usage) = memory::measure(|| solve(input));
+        best = best.min(start.elapsed());
+        answer = Some(solution?);
+        usage = Some(run_usage);
+    }
+    let answer = answer.unwrap();
+    print!("{name:<12} {answer} in {best:?} (best of {ITERATIONS})");
+    if memory::ENABLED {
+        print!(", {}", usage.unwrap());
+    }
+    println!();
+    Ok(answer)
+}
+
+fn main() -> AocResult<()> {
+    let megabytes = std::env::var("AOC_BENCH_MB")
+        .ok()
+        .map(|mb| mb.parse())
+        .transpose()?
+        .unwrap_or(64);
+    let path =
+        std::env::temp_dir().join(format!("aoc_problem_1_stress_{}.txt", std::process::id()));
+    write_stress_input(&path, megabytes)?;
+    println!("Problem 1 part 2 on {megabytes} MB of generated input");
+
+    let params = Params::default();
+    let result = bench("chars", &path, |input| {
+        chars::solve_p2_part_2(input.lines())
+    })
+    .and_then(|by_chars| {
+        let scanned = bench("byte scanner", &path, |input| {
+            problem_1::solve(Part::Two, input, &params)
+        })?;
+        anyhow::ensure!(by_chars == scanned, "The two versions disagree");
+        Ok(())
+    });
+    std::fs::remove_file(&path)?;
+    result
+}
@@ -0,0 +1,83 @@
+//! Times problem 3 on generated square schematics of growing size. With the
+//! cell to number index the time per cell should stay flat as they grow.
+//! Each is run with the whole board in memory and with the three row stream.
+//!
+//! Run with `cargo bench --bench problem_3`. Set `AOC_BENCH_SIZE` to change
+//! the side of the largest schematic, which defaults to 4000.
+
+use std::time::{Duration, Instant};
+
+use aoc_rust_2023::config::Params;
+use aoc_rust_2023::impls::problem_3;
+use aoc_rust_2023::{AocResult, Part};
+
+const ITERATIONS: u32 = 3;
+
+/// Mostly empty cells, with runs of digits and the odd symbol between them.
+fn generate_schematic(side: usize) -> Vec<String> {
+    let mut state: u64 = 0x2023_1203;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..side)
+        .map(|_| {
+            let mut line = String::with_capacity(side);
+            while line.len() < side {
+                match next() % 10 {
+                    0..=5 => line.push('.'),
+                    6..=8 => {
+                        for _ in 0..1 + next() % 3 {
+                            line.push((b'0' + (next() % 10) as u8) as char);
+                        }
+                        line.push('.');
+                    }
+                    _ => line.push(['*', '#', '+', '$', '/'][(next() % 5) as usize]),
+                }
+            }
+            line.truncate(side);
+            line
+        })
+        .collect()
+}
+
+fn main() -> AocResult<()> {
+    let largest = std::env::var("AOC_BENCH_SIZE")
+        .ok()
+        .map(|size| size.parse())
+        .transpose()?
+        .unwrap_or(4000);
+    let sides: Vec<usize> = [largest / 4, largest / 2, largest]
+        .into_iter()
+        .filter(|&side| side > 0)
+        .collect();
+
+    for side in sides {
+        let schematic = generate_schematic(side);
+        for (mode, part) in ["board", "stream"]
+            .into_iter()
+            .flat_map(|mode| [(mode, Part::One), (mode, Part::Two)])
+        {
+            let params: Params = [("mode".to_string(), mode.to_string())]
+                .into_iter()
+                .collect();
+            let mut best = Duration::MAX;
+            let mut answer = None;
+            for _ in 0..ITERATIONS {
+                let lines = schematic.iter().cloned().map(Ok);
+                let start = Instant::now();
+                answer = Some(problem_3::solve(part, lines, &params)?);
+                best = best.min(start.elapsed());
+            }
+            let cells = (side * side) as f64;
+            println!(
+                "{side}x{side} {mode} part {part}: {} in {best:?} (best of {ITERATIONS}), {:.1} ns per cell",
+                answer.unwrap(),
+                best.as_nanos() as f64 / cells
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{self, OutputFormat};
+use crate::runner::{self, Source};
+use crate::{helpers, impls, AocResult, Part};
+
+#[derive(Debug, Clone)]
+struct Job {
+    /// `None` is the default data directory.
+    profile: Option<String>,
+    problem: u8,
+    part: Part,
+    source: Source,
+}
+
+impl Job {
+    fn path(&self) -> PathBuf {
+        match self.source {
+            Source::Input => helpers::data_file_path_in(
+                config::get().profile_data_dir(self.profile.as_deref()),
+                self.problem,
+            ),
+            Source::Example => self.source.path(self.problem, self.part),
+        }
+    }
+}
+
+fn jobs(profiles: &[Option<String>], problems: &[u8], source: Source) -> Vec<Job> {
+    let mut jobs = Vec::new();
+    for profile in profiles {
+        for &problem in problems {
+            for part in [Part::One, Part::Two] {
+                jobs.push(Job {
+                    profile: profile.clone(),
+                    problem,
+                    part,
+                    source,
+                });
+            }
+        }
+    }
+    jobs
+}
+
+/// Describes how an answer compares to the expected one, if we know it.
+pub fn check_answer(
+    answer: &Result<String, String>,
+    expected: AocResult<Option<String>>,
+) -> String {
+    match (answer, expected) {
+        (Err(_), _) => "error".to_string(),
+        (Ok(answer), Ok(Some(expected))) if answer == &expected => "correct".to_string(),
+        (Ok(_), Ok(Some(expected))) => format!("WRONG, expected {expected}"),
+        (Ok(_), Ok(None)) => "unverified".to_string(),
+        (Ok(_), Err(e)) => format!("could not read expected answer: {e}"),
+    }
+}
+
+/// Prints a single result in the configured output format.
+pub fn print_result(
+    profile: Option<&str>,
+    problem: u8,
+    part: Part,
+    source: Source,
+    answer: &Result<String, String>,
+    status: &str,
+    elapsed: Duration,
+) {
+    match config::get().format {
+        OutputFormat::Text => {
+            let profile = profile.map(|p| format!("[{p}] ")).unwrap_or_default();
+            let answer = match answer {
+                Ok(answer) => answer.clone(),
+                Err(e) => format!("error: {e}"),
+            };
+            println!(
+                "{profile}Problem {problem} part {part} ({source}): {answer} [{status}] in {elapsed:?}"
+            );
+        }
+        OutputFormat::Json => {
+            let (answer, error) = match answer {
+                Ok(answer) => (Some(answer), None),
+                Err(e) => (None, Some(e)),
+            };
+            let result = serde_json::json!({
+                "year": config::get().year,
+                "profile": profile,
+                "problem": problem,
+                "part": u8::from(part),
+                "source": source.to_string(),
+                "answer": answer,
+                "error": error,
+                "status": status,
+                "elapsed_us": elapsed.as_micros() as u64,
+            });
+            println!("{result}");
+        }
+    }
+}
+
+/// Runs every solved problem and part against its input (in each of the given
+/// profiles) or example, printing each answer and whether it matches the
+/// expected one. Returns the number of runs that failed or were wrong.
+pub fn run_all(profiles: &[Option<String>], source: Source, threads: usize) -> AocResult<usize> {
+    let mut answer_stores = HashMap::new();
+    for profile in profiles {
+        let data_dir = config::get().profile_data_dir(profile.as_deref());
+        answer_stores.insert(profile.clone(), helpers::read_answer_store_in(data_dir)?);
+    }
+    let jobs = jobs(profiles, &impls::SOLVED_PROBLEMS, source);
+    let mut failures = 0;
+    runner::run_ordered(
+        &jobs,
+        threads,
+        |job| runner::run_file_unmeasured(job.problem, job.part, &job.path()),
+        |job, run| {
+            let (answer, elapsed) = match run {
+                Ok(run) => (
+                    run.answer
+                        .map(|solution| solution.to_string())
+                        .map_err(|e| e.to_string()),
+                    run.elapsed,
+                ),
+                Err(e) => (Err(format!("failed to open file: {e}")), Duration::ZERO),
+            };
+            let expected =
+                job.source
+                    .expected_answer(&answer_stores[&job.profile], job.problem, job.part);
+            let status = check_answer(&answer, expected);
+            if answer.is_err() || status.starts_with("WRONG") {
+                failures += 1;
+            }
+            print_result(
+                job.profile.as_deref(),
+                job.problem,
+                job.part,
+                job.source,
+                &answer,
+                &status,
+                elapsed,
+            );
+        },
+    );
+    Ok(failures)
+}
+
+/// Benchmarks every part of the given problems on their inputs. Runs are
+/// serial unless more than one thread is asked for, since parallel runs
+/// compete for the CPU and make timings noisier.
+pub fn bench_all(problems: &[u8], iterations: u32, threads: usize) {
+    let profile = config::get().profile.clone();
+    let jobs = jobs(&[profile], problems, Source::Input);
+    runner::run_ordered(
+        &jobs,
+        threads,
+        |job| runner::bench_file(job.problem, job.part, &job.path(), iterations),
+        |job, bench| {
+            let prefix = format!("Problem {} part {}", job.problem, job.part);
+            match bench {
+                Ok(bench) => {
+                    let answer = match bench.answer {
+                        Ok(solution) => solution.to_string(),
+                        Err(e) => format!("error: {e}"),
+                    };
+                    println!(
+                        "{prefix}: {answer}, {} iterations, min {:?}, mean {:?}, max {:?}",
+                        bench.iterations, bench.min, bench.mean, bench.max
+                    );
+                }
+                Err(e) => println!("{prefix}: failed to open file: {e}"),
+            }
+        },
+    );
+}
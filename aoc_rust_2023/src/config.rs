@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::AocResult;
+
+pub const CONFIG_FILE_NAME: &str = "aoc.toml";
+const DEFAULT_YEAR: u16 = 2023;
+const DEFAULT_DATA_DIR: &str = "aoc_rust_2023/src/data";
+const DEFAULT_EXAMPLES_DIR: &str = "aoc_rust_2023/src/data/examples";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Profile {
+    data_dir: PathBuf,
+}
+
+/// The contents of an `aoc.toml`. Every key is optional; relative paths are
+/// resolved against the directory the file was found in.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    year: Option<u16>,
+    data_dir: Option<PathBuf>,
+    examples_dir: Option<PathBuf>,
+    profile: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+    format: Option<OutputFormat>,
+    session_token_file: Option<PathBuf>,
+    timeout_secs: Option<u64>,
+    /// Solver parameters keyed by problem number, e.g. `[problems.2]`.
+    #[serde(default)]
+    problems: BTreeMap<String, toml::Table>,
+}
+
+/// Settings given on the command line. These win over the config file.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Overrides {
+    /// Use this config file instead of searching for aoc.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub year: Option<u16>,
+    #[arg(long, global = true)]
+    pub data_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub examples_dir: Option<PathBuf>,
+    /// Read inputs and answers from this profile's data directory
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// Give up on a solver after this many seconds
+    #[arg(long, global = true)]
+    pub timeout_secs: Option<u64>,
+    /// Solver parameter as key=value for every problem, or N.key=value for
+    /// problem N only, e.g. --param 2.bag=red=12,green=13,blue=14
+    #[arg(long = "param", global = true, value_parser = parse_param)]
+    pub params: Vec<(Option<u8>, String, String)>,
+    /// Number words for problem 1: en, fr, de, es or a file of `<word> <value>` lines
+    #[arg(long, global = true)]
+    pub dictionary: Option<String>,
+    /// Cubes in the bag for problem 2, e.g. --bag red=12,green=13,blue=14,yellow=4
+    #[arg(long, global = true)]
+    pub bag: Option<String>,
+}
+
+/// Reads `key=value`, or `N.key=value` to only give it to problem `N`.
+fn parse_param(param: &str) -> AocResult<(Option<u8>, String, String)> {
+    let (key, value) = param
+        .split_once('=')
+        .ok_or(anyhow::anyhow!("Param={param} is not key=value"))?;
+    let (problem, key) = match key.split_once('.') {
+        Some((problem, key)) => {
+            let problem = problem.trim().parse().map_err(|_| {
+                anyhow::anyhow!("Param={param} has {problem} as its problem number")
+            })?;
+            (Some(problem), key)
+        }
+        None => (None, key),
+    };
+    Ok((problem, key.trim().to_string(), value.trim().to_string()))
+}
+
+/// Parameters for a single problem's solver, as strings so each solver can
+/// parse them however it likes.
+#[derive(Debug, Clone, Default)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+}
+
+impl FromIterator<(String, String)> for Params {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub year: u16,
+    pub examples_dir: PathBuf,
+    pub profile: Option<String>,
+    pub format: OutputFormat,
+    pub session_token_file: Option<PathBuf>,
+    pub timeout: Option<Duration>,
+    data_dir: PathBuf,
+    profiles: BTreeMap<String, PathBuf>,
+    problems: BTreeMap<u8, BTreeMap<String, String>>,
+    /// `--param`s, each for one problem or (with `None`) for every problem.
+    cli_params: Vec<(Option<u8>, String, String)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: DEFAULT_YEAR,
+            examples_dir: PathBuf::from(DEFAULT_EXAMPLES_DIR),
+            profile: None,
+            format: OutputFormat::default(),
+            session_token_file: None,
+            timeout: None,
+            data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            profiles: BTreeMap::new(),
+            problems: BTreeMap::new(),
+            cli_params: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file (searching upwards from the current directory if
+    /// none is given) and applies the command line overrides on top.
+    pub fn load(overrides: Overrides) -> AocResult<Self> {
+        let config_path = match overrides.config {
+            Some(path) => Some(path),
+            None => find_config_file(&std::env::current_dir()?),
+        };
+        let mut config = match config_path {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+
+        if let Some(year) = overrides.year {
+            config.year = year;
+        }
+        if let Some(data_dir) = overrides.data_dir {
+            config.data_dir = data_dir;
+        }
+        if let Some(examples_dir) = overrides.examples_dir {
+            config.examples_dir = examples_dir;
+        }
+        if let Some(profile) = overrides.profile {
+            config.profile = Some(profile);
+        }
+        if let Some(format) = overrides.format {
+            config.format = format;
+        }
+        if let Some(timeout_secs) = overrides.timeout_secs {
+            config.timeout = Some(Duration::from_secs(timeout_secs));
+        }
+        if let Some(dictionary) = overrides.dictionary {
+            config
+                .cli_params
+                .push((Some(1), "dictionary".to_string(), dictionary));
+        }
+        if let Some(bag) = overrides.bag {
+            config.cli_params.push((Some(2), "bag".to_string(), bag));
+        }
+        config.cli_params.extend(overrides.params);
+
+        if let Some(profile) = &config.profile {
+            if !config.profiles.contains_key(profile) {
+                anyhow::bail!("Profile={profile} is not defined in {CONFIG_FILE_NAME}");
+            }
+        }
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> AocResult<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let file: ConfigFile = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let defaults = Self::default();
+
+        let mut problems = BTreeMap::new();
+        for (problem, table) in file.problems {
+            let mut params = BTreeMap::new();
+            for (key, value) in table {
+                let value = match value {
+                    toml::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                params.insert(key, value);
+            }
+            let problem: u8 = problem.parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "[problems.{problem}] in {} is not a problem number",
+                    path.display()
+                )
+            })?;
+            problems.insert(problem, params);
+        }
+
+        Ok(Self {
+            year: file.year.unwrap_or(defaults.year),
+            examples_dir: base_dir.join(file.examples_dir.unwrap_or(defaults.examples_dir)),
+            profile: file.profile,
+            format: file.format.unwrap_or_default(),
+            session_token_file: file.session_token_file.map(|path| base_dir.join(path)),
+            timeout: file.timeout_secs.map(Duration::from_secs),
+            data_dir: base_dir.join(file.data_dir.unwrap_or(defaults.data_dir)),
+            profiles: file
+                .profiles
+                .into_iter()
+                .map(|(name, profile)| (name, base_dir.join(profile.data_dir)))
+                .collect(),
+            problems,
+            cli_params: Vec::new(),
+        })
+    }
+
+    /// The data directory of the selected profile, or the default one.
+    pub fn data_dir(&self) -> &Path {
+        self.profile_data_dir(self.profile.as_deref())
+    }
+
+    /// Panics on unknown profiles, which `load` has already rejected for the
+    /// selected one.
+    pub fn profile_data_dir(&self, profile: Option<&str>) -> &Path {
+        match profile {
+            Some(profile) => &self.profiles[profile],
+            None => &self.data_dir,
+        }
+    }
+
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// The `[problems.N]` table from the config file, with the `--param`s
+    /// for this problem or every problem on top.
+    pub fn params(&self, problem: u8) -> Params {
+        let mut params = self.problems.get(&problem).cloned().unwrap_or_default();
+        for (only_for, key, value) in &self.cli_params {
+            if only_for.is_none_or(|only_for| only_for == problem) {
+                params.insert(key.clone(), value.clone());
+            }
+        }
+        Params(params)
+    }
+}
+
+fn find_config_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+pub fn init(config: Config) {
+    CONFIG
+        .set(config)
+        .expect("The config should only be set once, at startup");
+}
+
+/// The config set at startup, or the defaults if none was (e.g. in tests).
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_param, Config, OutputFormat};
+
+    #[test]
+    fn test_config_file_paths_are_relative_to_the_file() {
+        let dir = std::env::temp_dir().join(format!("aoc_config_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("aoc.toml");
+        std::fs::write(
+            &path,
+            r#"
+            data_dir = "inputs"
+            format = "json"
+            profile = "alice"
+
+            [profiles.alice]
+            data_dir = "alice"
+
+            [problems.2]
+            bag = "red=1,green=2,blue=3"
+            "#,
+        )
+        .unwrap();
+        let config = Config::from_file(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.format, OutputFormat::Json);
+        assert_eq!(config.data_dir(), dir.join("alice"));
+        assert_eq!(config.profile_data_dir(None), dir.join("inputs"));
+        assert_eq!(config.params(2).get("bag"), Some("red=1,green=2,blue=3"));
+        assert_eq!(config.params(3).get("bag"), None);
+    }
+
+    #[test]
+    fn test_cli_params_can_be_scoped_to_a_problem() {
+        let config = Config {
+            cli_params: vec![
+                parse_param("3.mode=stream").unwrap(),
+                parse_param("verbose = yes").unwrap(),
+            ],
+            ..Config::default()
+        };
+        assert_eq!(config.params(3).get("mode"), Some("stream"));
+        assert_eq!(config.params(5).get("mode"), None);
+        assert_eq!(config.params(5).get("verbose"), Some("yes"));
+        assert!(parse_param("x.mode=stream").is_err());
+    }
+}
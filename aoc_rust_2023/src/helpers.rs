@@ -1,14 +1,114 @@
+pub mod grid;
+pub mod matcher;
+pub mod num_set;
+pub mod number_words;
+
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::{config, AocResult, Part};
+
+const ANSWER_STORE: &str = "answers.txt";
+/// Inputs are read in chunks this big, so even very large ones only take a
+/// handful of reads.
+const READ_BUFFER_BYTES: usize = 1 << 20;
+
+/// The input for a problem in the selected profile's data directory.
+pub fn data_file_path(problem_num: u8) -> PathBuf {
+    data_file_path_in(config::get().data_dir(), problem_num)
+}
+
+pub fn data_file_path_in(data_dir: &Path, problem_num: u8) -> PathBuf {
+    data_dir.join(format!("problem_{problem_num}.txt"))
+}
+
+/// Examples are shared between both parts unless a part specific file exists,
+/// since some days (like problem 1) use a different example for part 2.
+pub fn example_file_path(problem_num: u8, part: Part) -> PathBuf {
+    let mut path = config::get().examples_dir.clone();
+    path.push(format!("problem_{problem_num}_part_{part}.txt"));
+    if path.exists() {
+        return path;
+    }
+    path.set_file_name(format!("problem_{problem_num}.txt"));
+    path
+}
+
+pub fn example_answer_path(problem_num: u8, part: Part) -> PathBuf {
+    let mut path = config::get().examples_dir.clone();
+    path.push(format!("problem_{problem_num}_part_{part}.answer"));
+    path
+}
+
+pub fn read_example_answer(problem_num: u8, part: Part) -> AocResult<Option<String>> {
+    let path = example_answer_path(problem_num, part);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let answer = std::fs::read_to_string(path)?;
+    Ok(Some(answer.trim().to_string()))
+}
+
+/// Reads the accepted answers for the selected profile's inputs.
+pub fn read_answer_store() -> AocResult<HashMap<(u8, Part), String>> {
+    read_answer_store_in(config::get().data_dir())
+}
 
-use crate::AocResult;
+/// Reads the `answers.txt` in a data directory, stored one per line as
+/// `<problem> <part> <answer>`. Blank lines and lines starting with `#` are
+/// ignored, and a missing file means no answers are known yet.
+pub fn read_answer_store_in(data_dir: &Path) -> AocResult<HashMap<(u8, Part), String>> {
+    let mut answers = HashMap::new();
+    let path = data_dir.join(ANSWER_STORE);
+    if !path.exists() {
+        return Ok(answers);
+    }
+    for line in open_file(&path)? {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut pieces = line.splitn(3, ' ');
+        let (Some(problem), Some(part), Some(answer)) =
+            (pieces.next(), pieces.next(), pieces.next())
+        else {
+            anyhow::bail!("Answer store line: {line} is not `<problem> <part> <answer>`");
+        };
+        let part = Part::try_from(part.parse::<u8>()?).map_err(anyhow::Error::msg)?;
+        answers.insert((problem.parse()?, part), answer.trim().to_string());
+    }
+    Ok(answers)
+}
+
+/// Opens a problem's input for reading with a large buffer.
+pub fn open_input(path: &Path) -> AocResult<BufReader<File>> {
+    Ok(BufReader::with_capacity(
+        READ_BUFFER_BYTES,
+        File::open(path)?,
+    ))
+}
+
+/// Calls `f` on every line of `input`, without its line ending. A single
+/// buffer is reused for every line, so nothing is allocated per line.
+pub fn for_each_line(
+    mut input: impl BufRead,
+    mut f: impl FnMut(&[u8]) -> AocResult<()>,
+) -> AocResult<()> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        let end = line.strip_suffix(b"\n").unwrap_or(&line);
+        f(end.strip_suffix(b"\r").unwrap_or(end))?;
+    }
+}
 
-pub fn open_data_file(
-    problem_num: u8,
-) -> AocResult<impl Iterator<Item = Result<String, std::io::Error>>> {
-    let mut path = PathBuf::from("aoc_rust_2023/src/data");
-    path.push(format!("problem_{problem_num}.txt"));
+pub fn open_file(path: &Path) -> AocResult<impl Iterator<Item = Result<String, std::io::Error>>> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     Ok(reader.lines())
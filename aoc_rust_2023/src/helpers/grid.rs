@@ -0,0 +1,245 @@
+use std::ops::{Index, IndexMut};
+
+use crate::AocResult;
+
+/// A rectangular grid stored row by row. Positions are `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Builds a grid with a row per line and a cell per char, mapped by
+    /// `cell`. Every line has to be the same length.
+    pub fn from_lines(
+        lines: impl Iterator<Item = Result<String, std::io::Error>>,
+        mut cell: impl FnMut(char) -> T,
+    ) -> AocResult<Self> {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut cols = 0;
+        for line in lines {
+            let line = line?;
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            let line_cols = cells.len() - before;
+            if rows == 0 {
+                cols = line_cols;
+            } else if line_cols != cols {
+                anyhow::bail!(
+                    "Line {} has {line_cols} columns, but the grid has {cols}",
+                    rows + 1
+                );
+            }
+            rows += 1;
+        }
+        Ok(Self { cells, rows, cols })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[self.index_of(position)])
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        self.contains(position).then(|| {
+            let idx = self.index_of(position);
+            &mut self.cells[idx]
+        })
+    }
+
+    /// The position's index in row by row order.
+    pub fn index_of(&self, (row, col): (usize, usize)) -> usize {
+        row * self.cols + col
+    }
+
+    pub fn position_of(&self, idx: usize) -> (usize, usize) {
+        (idx / self.cols, idx % self.cols)
+    }
+
+    /// Every position in row by row order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.cells.len()).map(|idx| self.position_of(idx))
+    }
+
+    fn offset_positions(
+        &self,
+        (row, col): (usize, usize),
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |&(row_offset, col_offset)| {
+            let position = (
+                row.checked_add_signed(row_offset)?,
+                col.checked_add_signed(col_offset)?,
+            );
+            self.contains(position).then_some(position)
+        })
+    }
+
+    /// The cells above, below, left and right that are inside the grid.
+    pub fn neighbours4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(position, &OFFSETS_4)
+    }
+
+    /// The cells touching this one, diagonals included, that are inside the
+    /// grid.
+    pub fn neighbours8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(position, &OFFSETS_8)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> + '_ {
+        (0..self.rows).map(move |row| &self[(row, col)])
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// Builds a `rows` by `cols` grid taking each cell from `source`.
+    fn rearranged(
+        &self,
+        rows: usize,
+        cols: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..rows * cols)
+            .map(|idx| self[source(idx / cols, idx % cols)].clone())
+            .collect();
+        Self { cells, rows, cols }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearranged(self.cols, self.rows, |row, col| (col, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearranged(self.cols, self.rows, |row, col| (self.rows - 1 - col, row))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearranged(self.cols, self.rows, |row, col| (col, self.cols - 1 - row))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        assert!(self.contains(position), "{position:?} is outside the grid");
+        &self.cells[self.index_of(position)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        assert!(self.contains(position), "{position:?} is outside the grid");
+        let idx = self.index_of(position);
+        &mut self.cells[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    fn grid(lines: &[&str]) -> Grid<char> {
+        Grid::from_lines(lines.iter().map(|line| Ok(line.to_string())), |c| c).unwrap()
+    }
+
+    #[test]
+    fn test_neighbours_stay_inside_the_grid() {
+        let mut grid = grid(&["abc", "def"]);
+        // Far enough out that working out the index would overflow.
+        assert_eq!(grid.get_mut((usize::MAX, 1)), None);
+        assert_eq!(grid.get_mut((1, 2)), Some(&mut 'f'));
+        let corner: Vec<_> = grid.neighbours8((0, 0)).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0), (1, 1)]);
+        let edge: Vec<_> = grid.neighbours4((1, 2)).collect();
+        assert_eq!(edge, vec![(0, 2), (1, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_reshaping() {
+        let grid = grid(&["abc", "def"]);
+        let rows = |grid: &Grid<char>| -> Vec<String> {
+            grid.iter_rows().map(|row| row.iter().collect()).collect()
+        };
+        assert_eq!(rows(&grid.transpose()), vec!["ad", "be", "cf"]);
+        assert_eq!(rows(&grid.rotate_clockwise()), vec!["da", "eb", "fc"]);
+        assert_eq!(
+            rows(&grid.rotate_counterclockwise()),
+            vec!["cf", "be", "ad"]
+        );
+        assert_eq!(grid.col(1).collect::<String>(), "be");
+        assert_eq!(grid.position_of(grid.index_of((1, 2))), (1, 2));
+        assert!(Grid::from_lines(["ab", "c"].iter().map(|l| Ok(l.to_string())), |c| c).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+const ROOT: usize = 0;
+const NO_LINK: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<V> {
+    /// Byte offset of the first byte of the match.
+    pub start: usize,
+    /// Byte offset one past the last byte of the match.
+    pub end: usize,
+    pub value: V,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    transitions: [usize; 256],
+    fail: usize,
+    /// The pattern ending at this node, as (length, value).
+    output: Option<(usize, V)>,
+    /// The next node along the fail links that has an output, so every
+    /// pattern ending at a position can be found without walking the whole
+    /// fail chain.
+    output_link: usize,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Self {
+            transitions: [NO_LINK; 256],
+            fail: ROOT,
+            output: None,
+            output_link: NO_LINK,
+        }
+    }
+}
+
+/// An Aho-Corasick automaton matching many byte patterns at once, each tagged
+/// with a value. Every overlapping match is found in a single pass, so words
+/// sharing letters like "oneight" report both "one" and "eight".
+#[derive(Debug, Clone)]
+pub struct MultiMatcher<V> {
+    nodes: Vec<Node<V>>,
+    /// Length of the longest pattern, which bounds how far past a match a
+    /// better one could still start.
+    longest: usize,
+}
+
+impl<V: Copy> MultiMatcher<V> {
+    /// Builds the automaton. Empty patterns are ignored, and if a pattern is
+    /// given more than once the last value wins.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut nodes = vec![Node::new()];
+        let mut longest = 0;
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            longest = longest.max(pattern.len());
+            let mut node = ROOT;
+            for &byte in pattern {
+                if nodes[node].transitions[byte as usize] == NO_LINK {
+                    nodes.push(Node::new());
+                    let new_node = nodes.len() - 1;
+                    nodes[node].transitions[byte as usize] = new_node;
+                }
+                node = nodes[node].transitions[byte as usize];
+            }
+            nodes[node].output = Some((pattern.len(), value));
+        }
+
+        // Breadth first, so a node's fail target is always finished before it.
+        let mut queue = std::collections::VecDeque::new();
+        for byte in 0..256 {
+            match nodes[ROOT].transitions[byte] {
+                NO_LINK => nodes[ROOT].transitions[byte] = ROOT,
+                child => queue.push_back(child),
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            let fail = nodes[node].fail;
+            nodes[node].output_link = if nodes[fail].output.is_some() {
+                fail
+            } else {
+                nodes[fail].output_link
+            };
+            for byte in 0..256 {
+                let fail_transition = nodes[fail].transitions[byte];
+                match nodes[node].transitions[byte] {
+                    NO_LINK => nodes[node].transitions[byte] = fail_transition,
+                    child => {
+                        nodes[child].fail = fail_transition;
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+        Self { nodes, longest }
+    }
+
+    /// The matches ending at `idx`, given the state reached by reading it.
+    fn matches_ending_at(&self, state: usize, idx: usize) -> impl Iterator<Item = Match<V>> + '_ {
+        let first_output = if self.nodes[state].output.is_some() {
+            state
+        } else {
+            self.nodes[state].output_link
+        };
+        std::iter::successors(
+            Some(first_output).filter(|&node| node != NO_LINK),
+            move |&node| Some(self.nodes[node].output_link).filter(|&node| node != NO_LINK),
+        )
+        .map(move |node| {
+            let (len, value) = self.nodes[node].output.unwrap();
+            Match {
+                start: idx + 1 - len,
+                end: idx + 1,
+                value,
+            }
+        })
+    }
+
+    /// Every match in `haystack`, including overlapping ones, ordered by where
+    /// they end (and longest first when several end at the same byte).
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = Match<V>> + 'a {
+        let mut state = ROOT;
+        haystack.iter().enumerate().flat_map(move |(idx, &byte)| {
+            state = self.nodes[state].transitions[byte as usize];
+            self.matches_ending_at(state, idx)
+        })
+    }
+
+    /// The match starting furthest left, preferring the longer one on ties.
+    /// Reading stops as soon as no later byte could start a better match, so
+    /// feeding the bytes back to front (to a matcher built from reversed
+    /// patterns) finds the last match without scanning the whole haystack.
+    pub fn leftmost(&self, haystack: impl IntoIterator<Item = u8>) -> Option<Match<V>> {
+        let mut best: Option<Match<V>> = None;
+        let mut state = ROOT;
+        for (idx, byte) in haystack.into_iter().enumerate() {
+            if best.is_some_and(|best| idx >= best.start + self.longest) {
+                break;
+            }
+            state = self.nodes[state].transitions[byte as usize];
+            for found in self.matches_ending_at(state, idx) {
+                let better = best.is_none_or(|best| {
+                    found.start < best.start || (found.start == best.start && found.end > best.end)
+                });
+                if better {
+                    best = Some(found);
+                }
+            }
+        }
+        best
+    }
+
+    /// The matches starting furthest left and furthest right, found in one
+    /// pass. Ties on the start go to the longer match.
+    pub fn first_and_last(&self, haystack: &[u8]) -> Option<(Match<V>, Match<V>)> {
+        let mut first_and_last: Option<(Match<V>, Match<V>)> = None;
+        for found in self.find_iter(haystack) {
+            let (first, last) = first_and_last.get_or_insert((found, found));
+            if found.start < first.start || (found.start == first.start && found.end > first.end) {
+                *first = found;
+            }
+            if found.start > last.start || (found.start == last.start && found.end > last.end) {
+                *last = found;
+            }
+        }
+        first_and_last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Match, MultiMatcher};
+
+    fn number_matcher() -> MultiMatcher<u32> {
+        MultiMatcher::new([
+            ("one", 1),
+            ("two", 2),
+            ("three", 3),
+            ("eight", 8),
+            ("1", 1),
+            ("2", 2),
+        ])
+    }
+
+    #[test]
+    fn test_find_iter_reports_overlapping_matches() {
+        let matcher = number_matcher();
+        let matches: Vec<Match<u32>> = matcher.find_iter(b"xtwoneight1").collect();
+        let found: Vec<(usize, u32)> = matches.iter().map(|m| (m.start, m.value)).collect();
+        assert_eq!(found, vec![(1, 2), (3, 1), (5, 8), (10, 1)]);
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let matcher = number_matcher();
+        let (first, last) = matcher.first_and_last(b"zoneight").unwrap();
+        assert_eq!((first.value, last.value), (1, 8));
+        let (first, last) = matcher.first_and_last(b"abc2xyz").unwrap();
+        assert_eq!((first.value, last.value), (2, 2));
+        assert!(matcher.first_and_last(b"nothing here").is_none());
+    }
+
+    #[test]
+    fn test_nested_patterns_prefer_leftmost_then_longest() {
+        let matcher = MultiMatcher::new([("seven", 7), ("even", 0), ("eve", 3)]);
+        let (first, last) = matcher.first_and_last(b"seventeen").unwrap();
+        assert_eq!((first.value, last.value), (7, 0));
+    }
+
+    #[test]
+    fn test_leftmost_stops_once_nothing_can_start_earlier() {
+        let matcher = MultiMatcher::new([("abcde", 1), ("c", 2), ("b", 3)]);
+        let found = matcher.leftmost(b"xabcdec".iter().copied()).unwrap();
+        assert_eq!((found.start, found.end, found.value), (1, 6, 1));
+        let found = matcher.leftmost(b"xabcd".iter().copied()).unwrap();
+        assert_eq!((found.start, found.value), (2, 3));
+        assert!(matcher.leftmost(b"xyz".iter().copied()).is_none());
+    }
+}
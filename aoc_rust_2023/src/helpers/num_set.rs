@@ -0,0 +1,117 @@
+/// Values below this go in the bitset; anything bigger falls back to a sorted
+/// list.
+const BITSET_BITS: u32 = 128;
+const WORDS: usize = (BITSET_BITS / 64) as usize;
+
+/// A set of small numbers, like the ones on a scratchcard. Most fit in a
+/// fixed size bitset, so building one doesn't allocate and intersecting two is
+/// a few popcounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumSet {
+    bits: [u64; WORDS],
+    /// Values too big for `bits`, sorted and without duplicates.
+    large: Vec<u32>,
+}
+
+impl NumSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the value wasn't already in the set.
+    pub fn insert(&mut self, value: u32) -> bool {
+        if value < BITSET_BITS {
+            let (word, bit) = (value as usize / 64, value % 64);
+            let fresh = self.bits[word] & (1 << bit) == 0;
+            self.bits[word] |= 1 << bit;
+            fresh
+        } else {
+            match self.large.binary_search(&value) {
+                Ok(_) => false,
+                Err(idx) => {
+                    self.large.insert(idx, value);
+                    true
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        if value < BITSET_BITS {
+            self.bits[value as usize / 64] & (1 << (value % 64)) != 0
+        } else {
+            self.large.binary_search(&value).is_ok()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>()
+            + self.large.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many values are in both sets.
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        let small: usize = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum();
+        let large = if other.large.is_empty() {
+            0
+        } else {
+            self.large
+                .iter()
+                .filter(|&&value| other.large.binary_search(&value).is_ok())
+                .count()
+        };
+        small + large
+    }
+
+    /// The values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..BITSET_BITS)
+            .filter(|&value| self.contains(value))
+            .chain(self.large.iter().copied())
+    }
+}
+
+impl FromIterator<u32> for NumSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumSet;
+
+    #[test]
+    fn test_small_and_large_values() {
+        let mut set: NumSet = [3, 64, 127, 128, 5000, 3].into_iter().collect();
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(127) && set.contains(5000) && !set.contains(4));
+        assert!(!set.insert(5000));
+        assert!(set.insert(200));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![3, 64, 127, 128, 200, 5000]
+        );
+
+        let other: NumSet = [0, 3, 127, 200, 4999].into_iter().collect();
+        assert_eq!(set.intersection_len(&other), 3);
+        assert_eq!(other.intersection_len(&set), 3);
+        assert!(NumSet::new().is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use super::matcher::MultiMatcher;
+
+const UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [(&str, u64); 8] = [
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+const SCALES: [(&str, u64); 3] = [
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Word {
+    Zero,
+    /// One to nineteen.
+    Unit(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+    And,
+}
+
+/// A written number found in some text, e.g. "one hundred and five".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberPhrase {
+    /// Byte offset of the first word.
+    pub start: usize,
+    /// Byte offset one past the last word.
+    pub end: usize,
+    pub value: u64,
+}
+
+/// Builds up the value of one phrase a word at a time, refusing words that
+/// can't follow the ones before them (like "two three" or "five thousand
+/// thousand").
+#[derive(Debug)]
+struct Phrase {
+    start: usize,
+    /// The end of the last word that is part of the number.
+    end: usize,
+    /// The end of the last word read, which may be a trailing "and".
+    read_to: usize,
+    last: Word,
+    /// Everything before the most recent scale word.
+    total: u64,
+    /// The part below the most recent scale word.
+    current: u64,
+    /// The last scale word used, since later ones must be smaller.
+    scale: Option<u64>,
+}
+
+impl Phrase {
+    fn start(word: Word, start: usize, end: usize) -> Option<Self> {
+        let current = match word {
+            Word::Zero => 0,
+            Word::Unit(value) | Word::Tens(value) => value,
+            Word::Hundred | Word::Scale(_) | Word::And => return None,
+        };
+        Some(Self {
+            start,
+            end,
+            read_to: end,
+            last: word,
+            total: 0,
+            current,
+            scale: None,
+        })
+    }
+
+    /// Adds the word if it can follow the phrase so far. `gap` is the text
+    /// between the last word read and this one.
+    fn push(&mut self, word: Word, gap: &[u8], end: usize) -> bool {
+        let spaced = !gap.is_empty() && gap.iter().all(u8::is_ascii_whitespace);
+        let hyphenated = gap == b"-";
+        let accepted = match (self.last, word) {
+            (Word::Tens(_), Word::Unit(value)) if value < 10 && (spaced || hyphenated) => {
+                self.current += value;
+                true
+            }
+            (Word::Hundred | Word::Scale(_) | Word::And, Word::Unit(value) | Word::Tens(value))
+                if spaced =>
+            {
+                self.current += value;
+                true
+            }
+            (Word::Unit(_), Word::Hundred) if spaced && self.current < 100 => {
+                self.current *= 100;
+                true
+            }
+            (Word::Unit(_) | Word::Tens(_) | Word::Hundred, Word::Scale(scale))
+                if spaced && self.scale.is_none_or(|last| scale < last) =>
+            {
+                self.total += self.current * scale;
+                self.current = 0;
+                self.scale = Some(scale);
+                true
+            }
+            (Word::Hundred | Word::Scale(_), Word::And) if spaced => true,
+            _ => false,
+        };
+        if accepted {
+            self.last = word;
+            self.read_to = end;
+            if word != Word::And {
+                self.end = end;
+            }
+        }
+        accepted
+    }
+
+    fn finish(self) -> NumberPhrase {
+        NumberPhrase {
+            start: self.start,
+            end: self.end,
+            value: self.total + self.current,
+        }
+    }
+}
+
+/// Finds English cardinal numbers written out in words, like "seven",
+/// "twenty-one", "one hundred and five" or "two million three hundred
+/// thousand", matching case insensitively and only on whole words.
+#[derive(Debug, Clone)]
+pub struct NumberWords {
+    matcher: MultiMatcher<Word>,
+}
+
+impl Default for NumberWords {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NumberWords {
+    pub fn new() -> Self {
+        let units = UNITS.iter().enumerate().map(|(value, &word)| {
+            let word_kind = match value {
+                0 => Word::Zero,
+                _ => Word::Unit(value as u64),
+            };
+            (word, word_kind)
+        });
+        let tens = TENS.iter().map(|&(word, value)| (word, Word::Tens(value)));
+        let scales = SCALES
+            .iter()
+            .map(|&(word, value)| (word, Word::Scale(value)));
+        let others = [("hundred", Word::Hundred), ("and", Word::And)];
+        Self {
+            matcher: MultiMatcher::new(units.chain(tens).chain(scales).chain(others)),
+        }
+    }
+
+    /// Every written number in `text`, in order, with the byte span it covers.
+    pub fn find_all(&self, text: &str) -> Vec<NumberPhrase> {
+        let text = text.as_bytes().to_ascii_lowercase();
+        let is_boundary = |idx: usize| {
+            idx == 0
+                || idx == text.len()
+                || !text[idx - 1].is_ascii_alphabetic()
+                || !text[idx].is_ascii_alphabetic()
+        };
+        let words = self
+            .matcher
+            .find_iter(&text)
+            .filter(|found| is_boundary(found.start) && is_boundary(found.end));
+
+        let mut phrases = Vec::new();
+        let mut phrase: Option<Phrase> = None;
+        for word in words {
+            if let Some(current) = &mut phrase {
+                if current.push(word.value, &text[current.read_to..word.start], word.end) {
+                    continue;
+                }
+                phrases.extend(phrase.take().map(Phrase::finish));
+            }
+            phrase = Phrase::start(word.value, word.start, word.end);
+        }
+        phrases.extend(phrase.map(Phrase::finish));
+        phrases
+    }
+
+    /// The value of `text` if it is exactly one written number, ignoring
+    /// surrounding whitespace.
+    pub fn parse(&self, text: &str) -> Option<u64> {
+        let text = text.trim();
+        match self.find_all(text).as_slice() {
+            [phrase] if phrase.start == 0 && phrase.end == text.len() => Some(phrase.value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NumberPhrase, NumberWords};
+
+    #[test]
+    fn test_parse() {
+        let words = NumberWords::new();
+        let cases = [
+            ("zero", Some(0)),
+            ("Twenty-One", Some(21)),
+            ("one hundred and five", Some(105)),
+            ("nineteen hundred", Some(1900)),
+            (
+                "two million three hundred thousand and twelve",
+                Some(2_300_012),
+            ),
+            ("forty two", Some(42)),
+            ("two three", None),
+            ("five thousand thousand", None),
+            ("eleven-one", None),
+            ("hundred", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(words.parse(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_find_all_reports_spans() {
+        let words = NumberWords::new();
+        let text =
+            "Take twenty-one steps, then one hundred and five more. Someone ate two, and three.";
+        let found: Vec<(&str, u64)> = words
+            .find_all(text)
+            .into_iter()
+            .map(|NumberPhrase { start, end, value }| (&text[start..end], value))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("twenty-one", 21),
+                ("one hundred and five", 105),
+                ("two", 2),
+                ("three", 3),
+            ]
+        );
+    }
+}
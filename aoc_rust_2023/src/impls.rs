@@ -1,6 +1,25 @@
+use std::io::BufRead;
+
+use crate::config::Params;
+use crate::{AocResult, Part, Solution};
+
 pub mod problem_1;
 pub mod problem_2;
 pub mod problem_3;
 pub mod problem_4;
 pub mod problem_5;
 pub mod problem_6;
+
+pub const SOLVED_PROBLEMS: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+pub fn solve(problem: u8, part: Part, input: impl BufRead, params: &Params) -> AocResult<Solution> {
+    match problem {
+        1 => problem_1::solve(part, input, params),
+        2 => problem_2::solve(part, input.lines(), params),
+        3 => problem_3::solve(part, input.lines(), params),
+        4 => problem_4::solve(part, input.lines()),
+        5 => problem_5::solve(part, input.lines(), params),
+        6 => problem_6::solve(part, input.lines()),
+        _ => anyhow::bail!("Problem {problem} has not been solved yet"),
+    }
+}
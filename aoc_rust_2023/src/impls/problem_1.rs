@@ -1,82 +1,285 @@
-use crate::{AocResult, Part, Solution};
+use std::io::BufRead;
+use std::path::Path;
 
-fn find_number(line_chars: impl Iterator<Item = char>) -> AocResult<u32> {
-    for maybe_digit in line_chars {
-        if let Some(digit) = maybe_digit.to_digit(10) {
-            return Ok(digit);
-        }
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::Params;
+use crate::helpers::{self, matcher::MultiMatcher};
+use crate::{memory, AocResult, Part, Solution};
+
+fn find_number<'a>(mut line_bytes: impl Iterator<Item = &'a u8>) -> AocResult<u32> {
+    match line_bytes.find(|byte| byte.is_ascii_digit()) {
+        Some(digit) => Ok((digit - b'0') as u32),
+        None => anyhow::bail!("The AOC input is broken?!?!?!?!?!?!?"),
     }
-    anyhow::bail!("The AOC input is broken?!?!?!?!?!?!?");
 }
 
-fn find_number_or_written_out_number(
-    line_chars: impl Iterator<Item = char>,
-    mini_string_maker: &[char],
-) -> AocResult<u32> {
-    for (index, maybe_digit) in line_chars.enumerate() {
-        if let Some(digit) = maybe_digit.to_digit(10) {
-            return Ok(digit);
+const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const ENGLISH: &[(&str, u32)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const FRENCH: &[(&str, u32)] = &[
+    ("un", 1),
+    ("une", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+const GERMAN: &[(&str, u32)] = &[
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+const SPANISH: &[(&str, u32)] = &[
+    ("uno", 1),
+    ("una", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+];
+
+/// Lowercases `text` and puts it in Unicode normal form C, so the same word
+/// always has the same bytes however it was typed.
+fn normalize(text: &str) -> String {
+    text.to_lowercase().nfc().collect()
+}
+
+/// The spelled out numbers part 2 looks for, on top of the digits. Words and
+/// lines are both normalized, so matching ignores case and whether accents
+/// are precomposed or combining.
+#[derive(Debug, Clone)]
+struct Dictionary {
+    words: Vec<(String, u32)>,
+}
+
+impl Dictionary {
+    fn from_words(words: &[(&str, u32)]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|&(word, value)| (normalize(word), value))
+                .collect(),
         }
-        let mini_str = &mini_string_maker[index..std::cmp::min(index + 5, mini_string_maker.len())];
-        match mini_str {
-            ['t', 'h', 'r', 'e', 'e'] | ['e', 'e', 'r', 'h', 't'] => return Ok(3),
-            ['s', 'e', 'v', 'e', 'n'] | ['n', 'e', 'v', 'e', 's'] => return Ok(7),
-            ['e', 'i', 'g', 'h', 't'] | ['t', 'h', 'g', 'i', 'e'] => return Ok(8),
-            _ => (),
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        let words = match name.to_lowercase().as_str() {
+            "en" | "english" => ENGLISH,
+            "fr" | "french" => FRENCH,
+            "de" | "german" => GERMAN,
+            "es" | "spanish" => SPANISH,
+            _ => return None,
         };
-        let mini_str = &mini_str[0..std::cmp::min(4, mini_str.len())];
-        match mini_str {
-            ['f', 'o', 'u', 'r'] | ['r', 'u', 'o', 'f'] => return Ok(4),
-            ['f', 'i', 'v', 'e'] | ['e', 'v', 'i', 'f'] => return Ok(5),
-            ['n', 'i', 'n', 'e'] | ['e', 'n', 'i', 'n'] => return Ok(9),
-            _ => (),
+        Some(Self::from_words(words))
+    }
+
+    /// Reads one `<word> <value>` pair per line. Blank lines and lines starting
+    /// with `#` are ignored.
+    fn from_file(path: &Path) -> AocResult<Self> {
+        let mut words = Vec::new();
+        for (idx, line) in helpers::open_file(path)?.enumerate() {
+            let line_number = idx + 1;
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, value) = line
+                .rsplit_once(char::is_whitespace)
+                .ok_or(anyhow::anyhow!(
+                    "Dictionary line {line_number}: {line} is not `<word> <value>`"
+                ))?;
+            let value = value.parse().map_err(|e| {
+                anyhow::anyhow!("Dictionary line {line_number}: {value} is not a number: {e}")
+            })?;
+            words.push((normalize(word.trim()), value));
+        }
+        Ok(Self { words })
+    }
+
+    /// Picks the dictionary named by the `dictionary` parameter, which is
+    /// either a built in language (en, fr, de, es) or a path to a word file.
+    /// English is used when none is given.
+    fn from_params(params: &Params) -> AocResult<Self> {
+        match params.get("dictionary") {
+            None => Ok(Self::from_words(ENGLISH)),
+            Some(name) => match Self::builtin(name) {
+                Some(dictionary) => Ok(dictionary),
+                None => Self::from_file(Path::new(name))
+                    .map_err(|e| anyhow::anyhow!("Failed to load dictionary {name}: {e}")),
+            },
+        }
+    }
+
+    fn scanner(&self) -> Scanner {
+        let words = || {
+            DIGITS.iter().copied().chain(
+                self.words
+                    .iter()
+                    .map(|(word, value)| (word.as_str(), *value)),
+            )
         };
-        let mini_str = &mini_str[0..std::cmp::min(3, mini_str.len())];
-        match mini_str {
-            ['o', 'n', 'e'] | ['e', 'n', 'o'] => return Ok(1),
-            ['t', 'w', 'o'] | ['o', 'w', 't'] => return Ok(2),
-            ['s', 'i', 'x'] | ['x', 'i', 's'] => return Ok(6),
-            _ => (),
+        Scanner {
+            forward: MultiMatcher::new(words().map(|(word, value)| (word.as_bytes(), value))),
+            backward: MultiMatcher::new(words().map(|(word, value)| {
+                let reversed: Vec<u8> = word.bytes().rev().collect();
+                (reversed, value)
+            })),
         }
     }
-    anyhow::bail!("The AOC input is broken?!?!?!?!?!?!?");
 }
 
-fn solve_p1_part_1(
-    lines: impl Iterator<Item = Result<String, std::io::Error>>,
-) -> AocResult<Solution> {
-    let mut sum = 0;
-    for line in lines {
-        let line = line?;
-        let first_digit = find_number(line.trim().chars())?;
-        let second_digit = find_number(line.trim().chars().rev())?;
-        sum += first_digit * 10 + second_digit;
+/// Finds the first number in a line by reading it from the front, and the
+/// last by reading it from the back with every word reversed. Neither scan
+/// allocates for ASCII lines.
+struct Scanner {
+    forward: MultiMatcher<u32>,
+    backward: MultiMatcher<u32>,
+}
+
+impl Scanner {
+    fn calibration_value(&self, line: &[u8]) -> AocResult<u32> {
+        let line = line.trim_ascii();
+        if !line.is_ascii() {
+            // ASCII is already normalized, so only these lines need the copy.
+            let line = normalize(std::str::from_utf8(line)?);
+            return self.first_and_last(line.as_bytes().iter().copied());
+        }
+        self.first_and_last(line.iter().map(u8::to_ascii_lowercase))
+    }
+
+    fn first_and_last(&self, line: impl DoubleEndedIterator<Item = u8> + Clone) -> AocResult<u32> {
+        let first = self.forward.leftmost(line.clone());
+        let last = self.backward.leftmost(line.rev());
+        match (first, last) {
+            (Some(first), Some(last)) => Ok(first.value * 10 + last.value),
+            _ => anyhow::bail!("The AOC input is broken?!?!?!?!?!?!?"),
+        }
     }
-    Ok(Solution::Int(sum as i32))
 }
 
-fn solve_p2_part_2(
-    lines: impl Iterator<Item = Result<String, std::io::Error>>,
-) -> AocResult<Solution> {
+fn solve_p1_part_1(input: impl BufRead) -> AocResult<Solution> {
     let mut sum = 0;
-    for line in lines {
-        let line = line?;
-        let char_array: Vec<char> = line.chars().collect();
-        let reversed_char_array: Vec<char> = line.chars().rev().collect();
-        let first_digit = find_number_or_written_out_number(line.trim().chars(), &char_array)?;
-        let second_digit =
-            find_number_or_written_out_number(line.trim().chars().rev(), &reversed_char_array)?;
-        sum += first_digit * 10 + second_digit;
-    }
-    Ok(Solution::Int(sum as i32))
+    // Lines are read and scanned together, so that's the parse step.
+    memory::track("parse", || {
+        helpers::for_each_line(input, |line| {
+            let first_digit = find_number(line.iter())?;
+            let second_digit = find_number(line.iter().rev())?;
+            sum += first_digit * 10 + second_digit;
+            Ok(())
+        })
+    })?;
+    Ok(Solution::Int(sum as i64))
+}
+
+fn solve_p2_part_2(input: impl BufRead, params: &Params) -> AocResult<Solution> {
+    let scanner = Dictionary::from_params(params)?.scanner();
+    let mut sum = 0;
+    memory::track("parse", || {
+        helpers::for_each_line(input, |line| {
+            sum += scanner.calibration_value(line)?;
+            Ok(())
+        })
+    })?;
+    Ok(Solution::Int(sum as i64))
 }
 
-pub fn solve(
-    part: Part,
-    lines: impl Iterator<Item = Result<String, std::io::Error>>,
-) -> AocResult<Solution> {
+/// Reads the input a line at a time into a reused buffer, so very large
+/// inputs are handled without allocating per line.
+pub fn solve(part: Part, input: impl BufRead, params: &Params) -> AocResult<Solution> {
     match part {
-        Part::One => solve_p1_part_1(lines),
-        Part::Two => solve_p2_part_2(lines),
+        Part::One => solve_p1_part_1(input),
+        Part::Two => solve_p2_part_2(input, params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dictionary;
+
+    #[test]
+    fn test_calibration_value_with_each_language() {
+        let cases = [
+            ("en", "zoneight234", 14),
+            ("en", "xTWOne3FOUR", 24),
+            ("fr", "huitrois7deux", 82),
+            ("de", "FÜNFzweiacht", 58),
+            ("de", "4sieben", 47),
+            ("es", "nueve1cuatrocinco", 95),
+            // A combining umlaut, where the dictionary has a precomposed one.
+            ("de", "fu\u{308}nf9", 59),
+        ];
+        for (language, line, expected) in cases {
+            let scanner = Dictionary::builtin(language).unwrap().scanner();
+            assert_eq!(
+                scanner.calibration_value(line.as_bytes()).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_words_are_normalized() {
+        let scanner = Dictionary::from_words(&[("cafe\u{301}", 7)]).scanner();
+        assert_eq!(scanner.calibration_value("xCAFÉx1".as_bytes()).unwrap(), 71);
+        assert_eq!(
+            scanner
+                .calibration_value("1cafe\u{301}".as_bytes())
+                .unwrap(),
+            17
+        );
+    }
+
+    #[test]
+    fn test_dictionary_file_errors_name_the_line() {
+        let path = std::env::temp_dir().join(format!("aoc_dictionary_test_{}", std::process::id()));
+        let error = |contents: &str| {
+            std::fs::write(&path, contents).unwrap();
+            Dictionary::from_file(&path).unwrap_err().to_string()
+        };
+        let no_value = error("# numbers\nuno 1\n\ndos\n");
+        let bad_value = error("uno 1\ndos two\n");
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(no_value, "Dictionary line 4: dos is not `<word> <value>`");
+        assert!(bad_value.starts_with("Dictionary line 2: two is not a number"));
     }
 }
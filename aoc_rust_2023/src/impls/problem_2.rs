@@ -1,118 +1,312 @@
-use crate::{AocResult, Part, Solution};
+use std::collections::BTreeMap;
 
-#[derive(Debug)]
-struct CubeResults {
-    red: u32,
-    blue: u32,
-    green: u32,
-}
+use crate::config::Params;
+use crate::{memory, AocResult, Part, Solution};
 
-impl CubeResults {
-    fn try_from_str(game_substr: &str) -> AocResult<Self> {
-        let mut parser = CubeResults {
-            red: 0,
-            blue: 0,
-            green: 0,
-        };
+pub mod bags;
+mod parser;
 
-        for game_result in game_substr.split(';') {
-            parser.handle_color_sections(game_result)?;
-        }
+pub use parser::ParseError;
+
+/// How many cubes of each color a bag holds.
+pub type Bag = BTreeMap<String, u32>;
+
+/// The puzzle's bag, used when the `bag` parameter isn't given.
+const DEFAULT_BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+/// How strictly game lines are read, set with the `parser` parameter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Lines must match the puzzle's grammar exactly, with errors giving the
+    /// column that didn't.
+    #[default]
+    Strict,
+    /// Anything that isn't a number followed by a color made of letters is
+    /// skipped, as are repeats of a color within a draw.
+    Lenient,
+}
 
-        Ok(parser)
+impl ParseMode {
+    fn from_params(params: &Params) -> AocResult<Self> {
+        match params.get("parser") {
+            None | Some("strict") => Ok(Self::Strict),
+            Some("lenient") => Ok(Self::Lenient),
+            Some(other) => anyhow::bail!("Parser={other} must be strict or lenient"),
+        }
     }
+}
 
-    fn handle_color_sections(&mut self, color_sections: &str) -> AocResult<()> {
-        for each_color in color_sections.split(',') {
-            let mut expect_color = false;
-            let mut color_to_increment = &mut self.blue;
-            let mut color_value = 0;
-            for piece in each_color.split(' ') {
-                if piece.is_empty() {
-                    continue;
-                }
-                if expect_color {
-                    match piece {
-                        "blue" => (),
-                        "green" => color_to_increment = &mut self.green,
-                        "red" => color_to_increment = &mut self.red,
-                        _ => anyhow::bail!("Expected a color, but piece: {piece} is not a color"),
-                    }
-                    *color_to_increment = std::cmp::max(color_value, *color_to_increment);
-                } else {
-                    match piece.parse::<u32>() {
-                        Ok(val) => {
-                            color_value = val;
-                            expect_color = true;
-                        }
-                        Err(_) => continue,
+/// The cubes of each color shown in one draw from the bag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Draw {
+    pub cubes: BTreeMap<String, u32>,
+}
+
+impl Draw {
+    /// Reads every `<n> <color>` pair it can find, where the color is made of
+    /// letters, skipping anything else. A color seen again in the same draw is
+    /// skipped too, since there's no telling which count is right.
+    fn parse_lenient(draw_substr: &str) -> Self {
+        let mut draw = Draw::default();
+        for each_color in draw_substr.split(',') {
+            let pieces: Vec<&str> = each_color.split(' ').filter(|p| !p.is_empty()).collect();
+            let mut idx = 0;
+            while idx < pieces.len() {
+                let value = pieces[idx].parse::<u32>();
+                match (value, pieces.get(idx + 1)) {
+                    (Ok(value), Some(color)) if color.chars().all(char::is_alphabetic) => {
+                        draw.cubes.entry(color.to_string()).or_insert(value);
+                        idx += 2;
                     }
+                    _ => idx += 1,
                 }
             }
         }
-        Ok(())
+        draw
     }
 
-    fn is_valid_for_game(&self, max_red: u32, max_green: u32, max_blue: u32) -> bool {
-        self.red <= max_red && self.green <= max_green && self.blue <= max_blue
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
     }
 
-    fn cube_power(&self) -> u32 {
-        self.blue * self.green * self.red
+    pub fn total(&self) -> u32 {
+        self.cubes.values().sum()
+    }
+
+    /// Colors the bag doesn't have can't be drawn at all.
+    pub fn fits_in(&self, bag: &Bag) -> bool {
+        self.cubes
+            .iter()
+            .all(|(color, &count)| count <= bag.get(color).copied().unwrap_or(0))
     }
 }
 
+/// How one color turned up across the draws of a game. Draws without the
+/// color aren't counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorStats {
+    pub draws: usize,
+    pub min: u32,
+    pub max: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    /// Parses a line like `Game 1: 3 blue, 4 red; 1 red, 2 green`.
+    pub fn parse(line: &str, mode: ParseMode) -> AocResult<Self> {
+        match mode {
+            ParseMode::Strict => Ok(parser::parse_game(line)?),
+            ParseMode::Lenient => Self::parse_lenient(line),
+        }
+    }
+
+    fn parse_lenient(line: &str) -> AocResult<Self> {
+        let (game_id_str, game_substr) = line
+            .split_once(':')
+            .ok_or(anyhow::anyhow!("No ':' found in game input: {line}"))?;
+        let draws = game_substr.split(';').map(Draw::parse_lenient).collect();
+        Ok(Self {
+            id: parse_game_id(game_id_str)?,
+            draws,
+        })
+    }
+
+    pub fn total_cubes(&self) -> u32 {
+        self.draws.iter().map(Draw::total).sum()
+    }
+
+    /// The index of the first draw that couldn't have come from `bag`.
+    pub fn first_invalid_draw(&self, bag: &Bag) -> Option<usize> {
+        self.draws.iter().position(|draw| !draw.fits_in(bag))
+    }
+
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.first_invalid_draw(bag).is_none()
+    }
+
+    pub fn color_stats(&self) -> BTreeMap<String, ColorStats> {
+        let mut stats: BTreeMap<String, ColorStats> = BTreeMap::new();
+        for (color, &count) in self.draws.iter().flat_map(|draw| &draw.cubes) {
+            stats
+                .entry(color.clone())
+                .and_modify(|stats| {
+                    stats.draws += 1;
+                    stats.min = stats.min.min(count);
+                    stats.max = stats.max.max(count);
+                    stats.total += count;
+                })
+                .or_insert(ColorStats {
+                    draws: 1,
+                    min: count,
+                    max: count,
+                    total: count,
+                });
+        }
+        stats
+    }
+
+    /// The fewest cubes of each color the bag could have held.
+    pub fn minimum_bag(&self) -> Bag {
+        self.color_stats()
+            .into_iter()
+            .map(|(color, stats)| (color, stats.max))
+            .collect()
+    }
+
+    /// The product of the minimum bag over every color that turned up.
+    pub fn power(&self) -> u64 {
+        self.minimum_bag()
+            .values()
+            .filter(|&&count| count > 0)
+            .map(|&count| count as u64)
+            .product()
+    }
+}
+
+/// Reads the `bag` parameter. Uses the puzzle's bag if it isn't given.
+fn parse_bag(params: &Params) -> AocResult<Bag> {
+    match params.get("bag") {
+        None => Ok(DEFAULT_BAG
+            .iter()
+            .map(|&(color, count)| (color.to_string(), count))
+            .collect()),
+        Some(bag_str) => parse_bag_str(bag_str),
+    }
+}
+
+/// Reads a bag like `red=12,green=13,blue=14,yellow=4`. Any color can be
+/// given, and the bag holds none of the colors that aren't.
+pub fn parse_bag_str(bag_str: &str) -> AocResult<Bag> {
+    let mut bag = Bag::new();
+    for color_count in bag_str.split(',').filter(|entry| !entry.trim().is_empty()) {
+        let (color, count) = color_count.split_once('=').ok_or(anyhow::anyhow!(
+            "Bag entry: {color_count} is not color=count"
+        ))?;
+        let color = color.trim();
+        if color.is_empty() {
+            anyhow::bail!("Bag entry: {color_count} has no color");
+        }
+        bag.insert(color.to_string(), count.trim().parse()?);
+    }
+    Ok(bag)
+}
+
 fn parse_game_id(game_id_substr: &str) -> AocResult<u32> {
     let game_id = game_id_substr
         .split(' ')
-        .last()
+        .next_back()
         .ok_or(anyhow::anyhow!("Game ID Substr: {game_id_substr} is empty"))?;
     let game_id = game_id.parse()?;
     Ok(game_id)
 }
 
-fn solve_p1_part_1(
+/// Parses every game, naming the line of any that fails.
+pub fn parse_games(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
-) -> AocResult<Solution> {
-    let mut sum = 0;
-    for line in lines {
+    params: &Params,
+) -> AocResult<Vec<Game>> {
+    let mode = ParseMode::from_params(params)?;
+    let mut games = Vec::new();
+    for (line_idx, line) in lines.enumerate() {
         let line = line?;
-        let (game_id_str, game_substr) = line
-            .split_once(':')
-            .ok_or(anyhow::anyhow!("No ':' found in game input: {line}"))?;
-        let game_id = parse_game_id(game_id_str)?;
-        let cube_values = CubeResults::try_from_str(game_substr)?;
-        println!("Game ID: {game_id}, cube results: {cube_values:?}");
-        if cube_values.is_valid_for_game(12, 13, 14) {
-            sum += game_id;
-        }
+        let game = Game::parse(&line, mode)
+            .map_err(|e| anyhow::anyhow!("Line {}, {e}: {line}", line_idx + 1))?;
+        games.push(game);
     }
-    Ok(Solution::Int(sum as i32))
+    Ok(games)
+}
+
+fn solve_p1_part_1(
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    params: &Params,
+) -> AocResult<Solution> {
+    let bag = parse_bag(params)?;
+    let games = memory::track("parse", || parse_games(lines, params))?;
+    let sum: u32 = games
+        .iter()
+        .filter(|game| game.is_possible_with(&bag))
+        .map(|game| game.id)
+        .sum();
+    Ok(Solution::Int(sum as i64))
 }
 
 fn solve_p2_part_2(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    params: &Params,
 ) -> AocResult<Solution> {
-    let mut sum = 0;
-    for line in lines {
-        let line = line?;
-        let (game_id_str, game_substr) = line
-            .split_once(':')
-            .ok_or(anyhow::anyhow!("No ':' found in game input: {line}"))?;
-        let game_id = parse_game_id(game_id_str)?;
-        let cube_values = CubeResults::try_from_str(game_substr)?;
-        println!("Game ID: {game_id}, cube results: {cube_values:?}");
-        sum += cube_values.cube_power();
-    }
-    Ok(Solution::Int(sum as i32))
+    let games = memory::track("parse", || parse_games(lines, params))?;
+    let sum: u64 = games.iter().map(Game::power).sum();
+    Ok(Solution::Int(sum as i64))
 }
 
 pub fn solve(
     part: Part,
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    params: &Params,
 ) -> AocResult<Solution> {
     match part {
-        Part::One => solve_p1_part_1(lines),
-        Part::Two => solve_p2_part_2(lines),
+        Part::One => solve_p1_part_1(lines, params),
+        Part::Two => solve_p2_part_2(lines, params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bag, Game, ParseMode};
+    use crate::config::Params;
+
+    #[test]
+    fn test_any_color_can_be_drawn() {
+        let game = Game::parse(
+            "Game 7: 3 blue, 4 yellow; 1 red, 2 yellow, 6 blue",
+            ParseMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(game.id, 7);
+        assert_eq!(game.power(), 6 * 4);
+
+        let mut bag = parse_bag(&Params::default()).unwrap();
+        assert_eq!(game.first_invalid_draw(&bag), Some(0));
+        bag.insert("yellow".to_string(), 4);
+        assert!(game.is_possible_with(&bag));
+    }
+
+    #[test]
+    fn test_draws_are_kept() {
+        let game = Game::parse(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            ParseMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(game.draws.len(), 3);
+        assert_eq!(game.draws[1].count("blue"), 6);
+        assert_eq!(game.total_cubes(), 18);
+        let red = game.color_stats()["red"];
+        assert_eq!((red.draws, red.min, red.max, red.total), (2, 1, 4, 5));
+        let bag = game.minimum_bag();
+        assert_eq!((bag["red"], bag["green"], bag["blue"]), (4, 2, 6));
+    }
+
+    #[test]
+    fn test_lenient_mode_skips_junk() {
+        let line = "Game 3: x red, 3 blue";
+        assert!(Game::parse(line, ParseMode::Strict).is_err());
+        let game = Game::parse(line, ParseMode::Lenient).unwrap();
+        assert_eq!(game.draws[0].count("blue"), 3);
+
+        let game =
+            Game::parse("Game 4: 4 4 blue, 2 red!, 1 red, 5 red", ParseMode::Lenient).unwrap();
+        let cubes: Vec<(&str, u32)> = game.draws[0]
+            .cubes
+            .iter()
+            .map(|(color, &count)| (color.as_str(), count))
+            .collect();
+        assert_eq!(cubes, vec![("blue", 4), ("red", 1)]);
     }
 }
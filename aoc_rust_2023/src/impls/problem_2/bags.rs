@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{Bag, Game};
+
+/// How one bag fares against a set of games.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BagReport {
+    pub possible: Vec<u32>,
+    pub id_sum: u32,
+    /// For each color the bag has too few of, how many games that rules out.
+    pub failing_colors: BTreeMap<String, usize>,
+}
+
+pub fn check_bag(games: &[Game], bag: &Bag) -> BagReport {
+    let mut possible = Vec::new();
+    let mut failing_colors = BTreeMap::new();
+    for game in games {
+        let short: Vec<String> = game
+            .minimum_bag()
+            .into_iter()
+            .filter(|(color, count)| *count > bag.get(color).copied().unwrap_or(0))
+            .map(|(color, _)| color)
+            .collect();
+        if short.is_empty() {
+            possible.push(game.id);
+        }
+        for color in short {
+            *failing_colors.entry(color).or_default() += 1;
+        }
+    }
+    BagReport {
+        id_sum: possible.iter().sum(),
+        possible,
+        failing_colors,
+    }
+}
+
+fn dominates(smaller: &Bag, larger: &Bag) -> bool {
+    smaller.iter().all(|(color, count)| *count <= larger[color])
+}
+
+/// Every bag that makes at least `at_least` of the games possible and can't
+/// lose a single cube without making fewer possible. Each color is limited by
+/// its own cubes, so when every game has to be possible there is exactly one.
+///
+/// Tries every combination of the values the games need for all but the last
+/// color, then picks the smallest last color that lets enough games through,
+/// so the work grows with the product of the distinct counts per color.
+pub fn minimal_bags(games: &[Game], at_least: usize) -> Vec<Bag> {
+    if at_least > games.len() {
+        return Vec::new();
+    }
+    let needs: Vec<Bag> = games.iter().map(Game::minimum_bag).collect();
+    let colors: Vec<String> = needs
+        .iter()
+        .flat_map(|need| need.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let need = |game: usize, color: &str| needs[game].get(color).copied().unwrap_or(0);
+    if at_least == 0 || colors.is_empty() {
+        return vec![colors.iter().map(|color| (color.clone(), 0)).collect()];
+    }
+
+    let (last_color, other_colors) = colors.split_last().unwrap();
+    let candidates: Vec<Vec<u32>> = other_colors
+        .iter()
+        .map(|color| {
+            let values: BTreeSet<u32> = (0..games.len()).map(|game| need(game, color)).collect();
+            values.into_iter().collect()
+        })
+        .collect();
+
+    let mut bags: Vec<Bag> = Vec::new();
+    let mut choice = vec![0; other_colors.len()];
+    loop {
+        let mut last_needs: Vec<u32> = (0..games.len())
+            .filter(|&game| {
+                other_colors
+                    .iter()
+                    .zip(&choice)
+                    .zip(&candidates)
+                    .all(|((color, &idx), values)| need(game, color) <= values[idx])
+            })
+            .map(|game| need(game, last_color))
+            .collect();
+        if last_needs.len() >= at_least {
+            last_needs.sort_unstable();
+            let mut bag: Bag = other_colors
+                .iter()
+                .zip(&choice)
+                .zip(&candidates)
+                .map(|((color, &idx), values)| (color.clone(), values[idx]))
+                .collect();
+            bag.insert(last_color.clone(), last_needs[at_least - 1]);
+            bags.push(bag);
+        }
+
+        // Step to the next combination, like an odometer.
+        let Some(digit) =
+            (0..choice.len()).find(|&digit| choice[digit] + 1 < candidates[digit].len())
+        else {
+            break;
+        };
+        choice[digit] += 1;
+        choice[..digit].fill(0);
+    }
+
+    let mut minimal: Vec<Bag> = Vec::new();
+    for bag in &bags {
+        let dominated = bags
+            .iter()
+            .any(|other| other != bag && dominates(other, bag));
+        if !dominated && !minimal.contains(bag) {
+            minimal.push(bag.clone());
+        }
+    }
+    minimal
+}
+
+/// Formats a bag the same way `--bag` takes it.
+pub fn format_bag(bag: &Bag) -> String {
+    bag.iter()
+        .map(|(color, count)| format!("{color}={count}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_bag, format_bag, minimal_bags};
+    use crate::impls::problem_2::{parse_bag_str, Game, ParseMode};
+
+    fn games() -> Vec<Game> {
+        [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red",
+        ]
+        .iter()
+        .map(|line| Game::parse(line, ParseMode::Strict).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_check_bag() {
+        let report = check_bag(&games(), &parse_bag_str("red=12,green=8,blue=5").unwrap());
+        assert_eq!(report.possible, vec![1, 2]);
+        assert_eq!(report.id_sum, 3);
+        assert_eq!(
+            report.failing_colors.into_iter().collect::<Vec<_>>(),
+            vec![("blue".to_string(), 1), ("red".to_string(), 1)]
+        );
+
+        // Colors that aren't listed aren't in the bag at all.
+        let report = check_bag(&games(), &parse_bag_str("red=12,blue=5").unwrap());
+        assert!(report.possible.is_empty());
+        assert_eq!(report.failing_colors["green"], 3);
+    }
+
+    #[test]
+    fn test_minimal_bags() {
+        let games = games();
+        let all: Vec<String> = minimal_bags(&games, 3).iter().map(format_bag).collect();
+        assert_eq!(all, vec!["blue=6,green=8,red=20"]);
+        let mut two: Vec<String> = minimal_bags(&games, 2).iter().map(format_bag).collect();
+        two.sort();
+        assert_eq!(two, vec!["blue=4,green=3,red=4"]);
+        let one: Vec<String> = minimal_bags(&games, 1).iter().map(format_bag).collect();
+        assert_eq!(one, vec!["blue=3,green=2,red=4", "blue=4,green=3,red=1"]);
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Draw, Game};
+
+/// Where and why a game line didn't match the grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based column of the offending character.
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reads `Game <id>: <n> <color>(, <n> <color>)*(; <n> <color>(, <n> <color>)*)*`,
+/// allowing extra spaces around the separators.
+struct Parser<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            column: self.line[..pos].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    fn eat(&mut self, literal: &str) -> bool {
+        let found = self.rest().starts_with(literal);
+        if found {
+            self.pos += literal.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        match self.eat(literal) {
+            true => Ok(()),
+            false => Err(self.error(self.pos, format!("expected `{literal}`"))),
+        }
+    }
+
+    fn skip_spaces(&mut self) -> usize {
+        let spaces = self.rest().len() - self.rest().trim_start_matches(' ').len();
+        self.pos += spaces;
+        spaces
+    }
+
+    fn require_spaces(&mut self) -> Result<(), ParseError> {
+        match self.skip_spaces() {
+            0 => Err(self.error(self.pos, "expected a space")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Consumes characters while `accept` holds, returning them.
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(accept).len();
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        let start = self.pos;
+        match self.take_while(|c| c.is_ascii_digit()) {
+            "" => Err(self.error(start, "expected a number")),
+            digits => digits
+                .parse()
+                .map_err(|_| self.error(start, format!("{digits} is too large"))),
+        }
+    }
+
+    fn draw(&mut self) -> Result<Draw, ParseError> {
+        let mut draw = Draw::default();
+        loop {
+            self.skip_spaces();
+            let count = self.number()?;
+            self.require_spaces()?;
+            let start = self.pos;
+            let color = match self.take_while(char::is_alphabetic) {
+                "" => return Err(self.error(start, "expected a color")),
+                color => color,
+            };
+            if draw.cubes.insert(color.to_string(), count).is_some() {
+                return Err(self.error(start, format!("{color} is drawn twice")));
+            }
+            self.skip_spaces();
+            if !self.eat(",") {
+                return Ok(draw);
+            }
+        }
+    }
+
+    fn game(&mut self) -> Result<Game, ParseError> {
+        self.skip_spaces();
+        self.expect("Game")?;
+        self.require_spaces()?;
+        let id = self.number()?;
+        self.expect(":")?;
+        let mut draws = vec![self.draw()?];
+        while self.eat(";") {
+            draws.push(self.draw()?);
+        }
+        if !self.rest().is_empty() {
+            return Err(self.error(self.pos, "expected `,`, `;` or the end of the line"));
+        }
+        Ok(Game { id, draws })
+    }
+}
+
+pub fn parse_game(line: &str) -> Result<Game, ParseError> {
+    Parser { line, pos: 0 }.game()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_game;
+
+    #[test]
+    fn test_errors_have_positions() {
+        let cases = [
+            ("Game 1: x red, 3", 9, "expected a number"),
+            ("Game 1: 3 red, 3", 17, "expected a space"),
+            ("Game 1: 3 red; 4", 17, "expected a space"),
+            ("Game 1 3 red", 7, "expected `:`"),
+            (
+                "Game 1: 3 red 4 blue",
+                15,
+                "expected `,`, `;` or the end of the line",
+            ),
+            ("Game 1: 3 red, 2 red", 18, "red is drawn twice"),
+            ("Gme 1: 3 red", 1, "expected `Game`"),
+        ];
+        for (line, column, message) in cases {
+            let error = parse_game(line).unwrap_err();
+            assert_eq!(
+                (error.column, error.message.as_str()),
+                (column, message),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_valid_line() {
+        let game = parse_game("Game 12: 3 blue, 4 red;1 red ,  2 green").unwrap();
+        assert_eq!(game.id, 12);
+        assert_eq!(game.draws.len(), 2);
+        assert_eq!(game.draws[1].count("green"), 2);
+    }
+}
@@ -1,169 +1,359 @@
-use std::collections::HashSet;
+use crate::config::Params;
+use crate::helpers::grid::Grid;
+use crate::{memory, AocResult, Part, Solution};
 
-use crate::{AocResult, Part, Solution};
+pub mod render;
+pub mod stream;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Span {
-    start: (usize, usize),
-    end: (usize, usize),
+pub struct Span {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+impl Span {
+    pub fn positions(self) -> impl Iterator<Item = (usize, usize)> {
+        let line_num = self.start.0;
+        (self.start.1..=self.end.1).map(move |char_idx| (line_num, char_idx))
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
-struct TaggedNum {
-    num: u32,
-    span: Span,
+pub struct TaggedNum {
+    pub num: u32,
+    pub span: Span,
 }
 
-#[derive(Debug)]
-struct Board {
-    symbol_positions: HashSet<(usize, usize)>,
-    nums: Vec<TaggedNum>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Digit(u32),
+    Symbol(char),
 }
 
-impl Board {
-    fn new() -> Self {
+impl Cell {
+    fn from_char(c: char) -> Self {
+        match c {
+            '.' => Self::Empty,
+            _ => match c.to_digit(10) {
+                Some(digit) => Self::Digit(digit),
+                None => Self::Symbol(c),
+            },
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Digit(digit) => char::from_digit(digit, 10).unwrap(),
+            Self::Symbol(symbol) => symbol,
+        }
+    }
+}
+
+/// How gear ratios combine their numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+}
+
+/// What makes a symbol a gear, read from the `gear_symbol` (a character, or
+/// `any`), `gear_neighbours` and `gear_combine` (`product` or `sum`)
+/// parameters. The puzzle's gears are `*` next to exactly two numbers,
+/// multiplied together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GearRule {
+    /// `None` lets any symbol be a gear.
+    pub symbol: Option<char>,
+    pub neighbours: usize,
+    pub combine: Combine,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
         Self {
-            symbol_positions: HashSet::new(),
-            nums: Vec::new(),
+            symbol: Some('*'),
+            neighbours: 2,
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl GearRule {
+    pub fn from_params(params: &Params) -> AocResult<Self> {
+        let mut rule = Self::default();
+        if let Some(symbol) = params.get("gear_symbol") {
+            let mut chars = symbol.chars();
+            rule.symbol = match (symbol, chars.next(), chars.next()) {
+                ("any", _, _) => None,
+                (_, Some(c), None) if Cell::from_char(c) == Cell::Symbol(c) => Some(c),
+                _ => anyhow::bail!("Gear_symbol={symbol} must be a single symbol or any"),
+            };
+        }
+        if let Some(neighbours) = params.get("gear_neighbours") {
+            rule.neighbours = neighbours.parse()?;
         }
+        rule.combine = match params.get("gear_combine") {
+            None | Some("product") => Combine::Product,
+            Some("sum") => Combine::Sum,
+            Some(other) => anyhow::bail!("Gear_combine={other} must be product or sum"),
+        };
+        Ok(rule)
+    }
+
+    fn matches(&self, symbol: char) -> bool {
+        self.symbol.is_none_or(|gear| gear == symbol)
     }
+}
+
+/// An engine schematic: every cell, the numbers in it, and which number (if
+/// any) covers each cell.
+#[derive(Debug)]
+pub struct Board {
+    grid: Grid<Cell>,
+    nums: Vec<TaggedNum>,
+    /// Which of `nums` covers each cell, so the numbers around a symbol can be
+    /// looked up directly.
+    num_ids: Grid<Option<usize>>,
+}
 
-    fn build_from_lines(
-        &mut self,
+impl Board {
+    pub fn build_from_lines(
         lines: impl Iterator<Item = Result<String, std::io::Error>>,
-    ) -> AocResult<()> {
-        for (line_index, line) in lines.enumerate() {
-            let line = line?;
-            let mut eval_num = false;
-            let mut start_span = [line_index, 0];
-            let mut num_substr = Vec::new();
-            for (char_index, c) in line.chars().enumerate() {
-                if eval_num && c.is_ascii_digit() {
-                    num_substr.push(c);
-                } else if eval_num {
-                    eval_num = false;
-                    let num_str_buffer = num_substr.drain(..);
-                    let number_str = String::from_iter(num_str_buffer);
-                    let number: u32 = number_str.parse().unwrap();
-                    let tagged_num = TaggedNum {
-                        num: number,
-                        span: Span {
-                            start: (start_span[0], start_span[1]),
-                            end: (line_index, char_index - 1),
-                        },
-                    };
-                    self.nums.push(tagged_num);
-                    start_span = [line_index, 0];
-                    if c != '.' {
-                        self.symbol_positions.insert((line_index, char_index));
+    ) -> AocResult<Self> {
+        let grid = Grid::from_lines(lines, Cell::from_char)?;
+        let mut nums = Vec::new();
+        let mut num_ids = Grid::new(grid.rows(), grid.cols(), None);
+        for (line_index, row) in grid.iter_rows().enumerate() {
+            let mut current: Option<TaggedNum> = None;
+            for (char_index, &cell) in row.iter().enumerate() {
+                match (cell, &mut current) {
+                    (Cell::Digit(digit), Some(tagged_num)) => {
+                        tagged_num.num = tagged_num.num * 10 + digit;
+                        tagged_num.span.end = (line_index, char_index);
+                    }
+                    (Cell::Digit(digit), None) => {
+                        current = Some(TaggedNum {
+                            num: digit,
+                            span: Span {
+                                start: (line_index, char_index),
+                                end: (line_index, char_index),
+                            },
+                        });
                     }
-                } else if !eval_num && c != '.' && !c.is_ascii_digit() {
-                    self.symbol_positions.insert((line_index, char_index));
-                } else if !eval_num && c != '.' && c.is_ascii_digit() {
-                    eval_num = true;
-                    start_span[1] = char_index;
-                    num_substr.push(c);
+                    _ => nums.extend(current.take()),
+                }
+                if current.is_some() {
+                    num_ids[(line_index, char_index)] = Some(nums.len());
                 }
             }
-            if eval_num {
-                let num_str_buffer = num_substr.drain(..);
-                let number_str = String::from_iter(num_str_buffer);
-                let number: u32 = number_str.parse().unwrap();
-                let tagged_num = TaggedNum {
-                    num: number,
-                    span: Span {
-                        start: (start_span[0], start_span[1]),
-                        end: (line_index, line.len() - 1),
-                    },
-                };
-                self.nums.push(tagged_num);
-            }
+            nums.extend(current);
         }
-        Ok(())
+        Ok(Self {
+            grid,
+            nums,
+            num_ids,
+        })
     }
 
-    fn sum_parts(&self) -> u32 {
-        let mut sum = 0;
-        for &tagged_num in &self.nums {
-            'outer_loop: for position in Self::span_iter(tagged_num.span) {
-                for adjacent_position in Self::adjacent_positions(position) {
-                    if self.symbol_positions.contains(&adjacent_position) {
-                        sum += tagged_num.num;
-                        break 'outer_loop;
-                    }
+    pub fn grid(&self) -> &Grid<Cell> {
+        &self.grid
+    }
+
+    /// Every number, indexed by its id.
+    pub fn nums(&self) -> &[TaggedNum] {
+        &self.nums
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = ((usize, usize), char)> + '_ {
+        self.grid
+            .positions()
+            .filter_map(|position| match self.grid[position] {
+                Cell::Symbol(symbol) => Some((position, symbol)),
+                _ => None,
+            })
+    }
+
+    /// The ids of the distinct numbers touching a position, in the order
+    /// they're found.
+    pub fn nums_around(&self, position: (usize, usize)) -> Vec<usize> {
+        let mut ids = Vec::new();
+        for adjacent_position in self.grid.neighbours8(position) {
+            if let Some(id) = self.num_ids[adjacent_position] {
+                if !ids.contains(&id) {
+                    ids.push(id);
                 }
             }
         }
-        sum
+        ids
     }
 
-    fn calculate_gear_ratios(&self) -> u32 {
-        let mut sum = 0;
-        for &symbol_position in &self.symbol_positions {
-            let mut adjacent_num_count = 0;
-            let mut adjacent_nums = [0, 0];
-            'advance_symbol: for &tagged_num in &self.nums {
-                for position in Self::span_iter(tagged_num.span) {
-                    for adjacent_position in Self::adjacent_positions(position) {
-                        if symbol_position == adjacent_position {
-                            adjacent_nums[adjacent_num_count] = tagged_num.num;
-                            adjacent_num_count += 1;
-                            continue 'advance_symbol;
-                        }
+    /// The ids of every number touching a `symbol`, each once.
+    pub fn nums_adjacent_to(&self, symbol: char) -> Vec<usize> {
+        let mut adjacent = vec![false; self.nums.len()];
+        for (position, _) in self.symbols().filter(|&(_, found)| found == symbol) {
+            for id in self.nums_around(position) {
+                adjacent[id] = true;
+            }
+        }
+        (0..self.nums.len()).filter(|&id| adjacent[id]).collect()
+    }
+
+    /// The symbols touching a number, each with its position.
+    pub fn symbols_adjacent_to(&self, id: usize) -> Vec<((usize, usize), char)> {
+        let mut symbols = Vec::new();
+        for position in self.nums[id].span.positions() {
+            for adjacent_position in self.grid.neighbours8(position) {
+                if let Cell::Symbol(symbol) = self.grid[adjacent_position] {
+                    if !symbols.contains(&(adjacent_position, symbol)) {
+                        symbols.push((adjacent_position, symbol));
                     }
                 }
             }
-            if adjacent_num_count == 2 {
-                sum += adjacent_nums[0] * adjacent_nums[1];
+        }
+        symbols
+    }
+
+    /// Whether each number (by id) touches any symbol.
+    pub fn part_flags(&self) -> Vec<bool> {
+        let mut is_part = vec![false; self.nums.len()];
+        for (position, _) in self.symbols() {
+            for id in self.nums_around(position) {
+                is_part[id] = true;
             }
         }
-        sum
+        is_part
     }
 
-    fn span_iter(span: Span) -> impl Iterator<Item = (usize, usize)> {
-        let line_num = span.start.0;
-        (span.start.1..=span.end.1).map(move |char_idx| (line_num, char_idx))
+    pub fn sum_parts(&self) -> u64 {
+        self.nums
+            .iter()
+            .zip(self.part_flags())
+            .filter(|(_, is_part)| *is_part)
+            .map(|(tagged_num, _)| tagged_num.num as u64)
+            .sum()
     }
 
-    fn adjacent_positions(position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
-        [
-            (position.0, position.1.saturating_sub(1)),
-            (position.0, position.1 + 1),
-            (position.0 + 1, position.1),
-            (position.0.saturating_sub(1), position.1),
-            (position.0.saturating_sub(1), position.1.saturating_sub(1)),
-            (position.0.saturating_sub(1), position.1 + 1),
-            (position.0 + 1, position.1.saturating_sub(1)),
-            (position.0 + 1, position.1 + 1),
-        ]
-        .into_iter()
+    /// Every gear under `rule`, with the ids of the numbers around it.
+    pub fn gears(&self, rule: &GearRule) -> Vec<((usize, usize), Vec<usize>)> {
+        self.symbols()
+            .filter(|&(_, symbol)| rule.matches(symbol))
+            .map(|(position, _)| (position, self.nums_around(position)))
+            .filter(|(_, ids)| ids.len() == rule.neighbours)
+            .collect()
+    }
+
+    pub fn calculate_gear_ratios(&self, rule: &GearRule) -> u64 {
+        let mut sum = 0;
+        for (_, ids) in self.gears(rule) {
+            let values = ids.iter().map(|&id| self.nums[id].num as u64);
+            sum += match rule.combine {
+                Combine::Product => values.product::<u64>(),
+                Combine::Sum => values.sum(),
+            };
+        }
+        sum
     }
 }
 
+/// How to read the schematic, from the `mode` parameter: `board` (the
+/// default) keeps all of it in memory, `stream` only three rows at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Board,
+    Stream,
+}
+
+impl Mode {
+    fn from_params(params: &Params) -> AocResult<Self> {
+        match params.get("mode") {
+            None | Some("board") => Ok(Self::Board),
+            Some("stream") => Ok(Self::Stream),
+            Some(other) => anyhow::bail!("Mode={other} must be board or stream"),
+        }
+    }
+}
+
+fn solve_streaming(
+    part: Part,
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    rule: &GearRule,
+) -> AocResult<Solution> {
+    let mut sum = 0;
+    stream::stream(lines, rule, |found| match (part, found) {
+        (Part::One, stream::Found::Part { num, .. }) => sum += num as u64,
+        (Part::Two, stream::Found::Gear { ratio, .. }) => sum += ratio,
+        _ => {}
+    })?;
+    Ok(Solution::Int(sum as i64))
+}
+
 fn solve_p1_part_1(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
-    let mut board = Board::new();
-    board.build_from_lines(lines)?;
+    let board = memory::track("parse", || Board::build_from_lines(lines))?;
     let sum = board.sum_parts();
-    Ok(Solution::Int(sum as i32))
+    Ok(Solution::Int(sum as i64))
 }
 
 fn solve_p2_part_2(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    rule: &GearRule,
 ) -> AocResult<Solution> {
-    let mut board = Board::new();
-    board.build_from_lines(lines)?;
-    let sum = board.calculate_gear_ratios();
-    Ok(Solution::Int(sum as i32))
+    let board = memory::track("parse", || Board::build_from_lines(lines))?;
+    let sum = board.calculate_gear_ratios(rule);
+    Ok(Solution::Int(sum as i64))
 }
 
 pub fn solve(
     part: Part,
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    params: &Params,
 ) -> AocResult<Solution> {
-    match part {
-        Part::One => solve_p1_part_1(lines),
-        Part::Two => solve_p2_part_2(lines),
+    let rule = GearRule::from_params(params)?;
+    match (Mode::from_params(params)?, part) {
+        (Mode::Stream, _) => solve_streaming(part, lines, &rule),
+        (Mode::Board, Part::One) => solve_p1_part_1(lines),
+        (Mode::Board, Part::Two) => solve_p2_part_2(lines, &rule),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Board, Combine, GearRule};
+
+    fn board() -> Board {
+        let lines = ["12.3.", "..*..", ".4.#5", "...6."];
+        Board::build_from_lines(lines.iter().map(|line| Ok(line.to_string()))).unwrap()
+    }
+
+    #[test]
+    fn test_gears_with_any_number_of_neighbours() {
+        let board = board();
+        assert_eq!(board.calculate_gear_ratios(&GearRule::default()), 0);
+        let three = GearRule {
+            neighbours: 3,
+            ..GearRule::default()
+        };
+        assert_eq!(board.calculate_gear_ratios(&three), 12 * 3 * 4);
+        let summed = GearRule {
+            symbol: Some('#'),
+            combine: Combine::Sum,
+            ..GearRule::default()
+        };
+        assert_eq!(board.calculate_gear_ratios(&summed), 5 + 6);
+    }
+
+    #[test]
+    fn test_adjacency_queries() {
+        let board = board();
+        let values = |ids: Vec<usize>| -> Vec<u32> {
+            ids.into_iter().map(|id| board.nums()[id].num).collect()
+        };
+        assert_eq!(values(board.nums_adjacent_to('*')), vec![12, 3, 4]);
+        assert_eq!(values(board.nums_adjacent_to('#')), vec![5, 6]);
+        let six = board.nums().iter().position(|num| num.num == 6).unwrap();
+        assert_eq!(board.symbols_adjacent_to(six), vec![((2, 3), '#')]);
     }
 }
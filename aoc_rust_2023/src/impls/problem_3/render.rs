@@ -0,0 +1,250 @@
+use std::io::Write;
+use std::ops::Range;
+
+use super::{Board, Cell, GearRule};
+use crate::helpers::grid::Grid;
+use crate::AocResult;
+
+/// What a cell is drawn as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Empty,
+    /// A digit of a number touching a symbol.
+    Part,
+    /// A digit of a number touching no symbol.
+    Rejected,
+    Gear,
+    Symbol,
+}
+
+impl Style {
+    fn ansi(self) -> &'static str {
+        match self {
+            Self::Empty => "\x1b[2m",
+            Self::Part => "\x1b[32m",
+            Self::Rejected => "\x1b[31m",
+            Self::Gear => "\x1b[1;33m",
+            Self::Symbol => "\x1b[36m",
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Self::Empty => [40, 40, 40],
+            Self::Part => [80, 200, 80],
+            Self::Rejected => [220, 60, 60],
+            Self::Gear => [250, 210, 40],
+            Self::Symbol => [60, 180, 220],
+        }
+    }
+
+    fn hex(self) -> String {
+        let [r, g, b] = self.rgb();
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+/// Styles every cell: numbers by whether their span touches a symbol, and
+/// symbols by whether they are gears under `rule`.
+pub fn styles(board: &Board, rule: &GearRule) -> Grid<Style> {
+    let mut styles = board.grid().map(|cell| match cell {
+        Cell::Symbol(_) => Style::Symbol,
+        _ => Style::Empty,
+    });
+    for (tagged_num, is_part) in board.nums().iter().zip(board.part_flags()) {
+        let style = if is_part {
+            Style::Part
+        } else {
+            Style::Rejected
+        };
+        for position in tagged_num.span.positions() {
+            styles[position] = style;
+        }
+    }
+    for (position, _) in board.gears(rule) {
+        styles[position] = Style::Gear;
+    }
+    styles
+}
+
+/// The part of the board to draw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct View {
+    pub rows: Range<usize>,
+    pub cols: Range<usize>,
+}
+
+impl View {
+    pub fn whole(board: &Board) -> Self {
+        Self {
+            rows: 0..board.grid().rows(),
+            cols: 0..board.grid().cols(),
+        }
+    }
+
+    /// The cells within `radius` of `(row, col)`, cut off at the edges. Fails
+    /// if none of them are on the board.
+    pub fn around(board: &Board, (row, col): (usize, usize), radius: usize) -> AocResult<Self> {
+        let whole = Self::whole(board);
+        let view = Self {
+            rows: row.saturating_sub(radius)
+                ..row
+                    .saturating_add(radius)
+                    .saturating_add(1)
+                    .min(whole.rows.end),
+            cols: col.saturating_sub(radius)
+                ..col
+                    .saturating_add(radius)
+                    .saturating_add(1)
+                    .min(whole.cols.end),
+        };
+        if view.rows.is_empty() || view.cols.is_empty() {
+            anyhow::bail!(
+                "Crop={row},{col} with radius {radius} is off the {}x{} board",
+                whole.rows.end,
+                whole.cols.end
+            );
+        }
+        Ok(view)
+    }
+
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows
+            .clone()
+            .flat_map(|row| self.cols.clone().map(move |col| (row, col)))
+    }
+}
+
+pub fn to_ansi(board: &Board, styles: &Grid<Style>, view: &View) -> String {
+    let mut out = String::new();
+    for row in view.rows.clone() {
+        for col in view.cols.clone() {
+            out.push_str(styles[(row, col)].ansi());
+            out.push(board.grid()[(row, col)].to_char());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+const SVG_CELL: usize = 12;
+
+pub fn to_svg(board: &Board, styles: &Grid<Style>, view: &View) -> String {
+    let width = view.cols.len() * SVG_CELL;
+    let height = view.rows.len() * SVG_CELL;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\n",
+        SVG_CELL - 2
+    );
+    for (row, col) in view.positions() {
+        let x = (col - view.cols.start) * SVG_CELL;
+        let y = (row - view.rows.start) * SVG_CELL;
+        let style = styles[(row, col)];
+        if style == Style::Gear {
+            out.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" fill=\"#604800\"/>\n"
+            ));
+        }
+        let c = match board.grid()[(row, col)].to_char() {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            c => c.to_string(),
+        };
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"{}\">{c}</text>\n",
+            x + SVG_CELL / 2,
+            y + SVG_CELL - 3,
+            style.hex()
+        ));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+const PNG_CELL: usize = 4;
+
+/// A PNG with a square of colour per cell. There's no text, so it stays
+/// readable even for whole schematics.
+pub fn to_png(styles: &Grid<Style>, view: &View) -> AocResult<Vec<u8>> {
+    let width = view.cols.len() * PNG_CELL;
+    let height = view.rows.len() * PNG_CELL;
+    let mut pixels = Vec::with_capacity(height * (width * 3 + 1));
+    for row in view.rows.clone() {
+        let mut scanline = vec![0];
+        for col in view.cols.clone() {
+            for _ in 0..PNG_CELL {
+                scanline.extend(styles[(row, col)].rgb());
+            }
+        }
+        for _ in 0..PNG_CELL {
+            pixels.extend(&scanline);
+        }
+    }
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&pixels)?;
+    let mut header = Vec::new();
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    // 8 bit RGB, default compression and filtering, no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [
+        (b"IHDR", header),
+        (b"IDAT", encoder.finish()?),
+        (b"IEND", Vec::new()),
+    ] {
+        png.extend((data.len() as u32).to_be_bytes());
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(kind);
+        crc.update(&data);
+        png.extend(kind);
+        png.extend(data);
+        png.extend(crc.finalize().to_be_bytes());
+    }
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{styles, to_ansi, to_png, Style, View};
+    use crate::impls::problem_3::{Board, GearRule};
+
+    #[test]
+    fn test_styles_and_crop() {
+        let lines = ["467..114..", "...*......", "..35..633."];
+        let board = Board::build_from_lines(lines.iter().map(|line| Ok(line.to_string()))).unwrap();
+        let styles = styles(&board, &GearRule::default());
+        assert_eq!(styles[(0, 0)], Style::Part);
+        assert_eq!(styles[(0, 5)], Style::Rejected);
+        assert_eq!(styles[(1, 3)], Style::Gear);
+        assert_eq!(styles[(2, 6)], Style::Rejected);
+
+        assert_eq!(
+            View::around(&board, (1, 3), usize::MAX).unwrap(),
+            View::whole(&board)
+        );
+        assert!(View::around(&board, (1, 20), 5).is_err());
+        let view = View::around(&board, (1, 3), 1).unwrap();
+        assert_eq!(
+            view,
+            View {
+                rows: 0..3,
+                cols: 2..5
+            }
+        );
+        let plain: String = to_ansi(&board, &styles, &view)
+            .split('\x1b')
+            .map(|piece| piece.split_once('m').map_or(piece, |(_, text)| text))
+            .collect();
+        assert_eq!(plain, "7..\n.*.\n35.\n");
+
+        let png = to_png(&styles, &view).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+    }
+}
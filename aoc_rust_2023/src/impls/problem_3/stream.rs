@@ -0,0 +1,180 @@
+use super::{Cell, Combine, GearRule};
+use crate::AocResult;
+
+/// Something found while streaming a schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Found {
+    Part {
+        row: usize,
+        num: u32,
+    },
+    Gear {
+        position: (usize, usize),
+        ratio: u64,
+    },
+}
+
+#[derive(Debug)]
+struct RowNum {
+    start: usize,
+    end: usize,
+    num: u32,
+}
+
+/// One row of the schematic with its numbers already picked out.
+#[derive(Debug)]
+struct Row {
+    index: usize,
+    cells: Vec<Cell>,
+    nums: Vec<RowNum>,
+}
+
+impl Row {
+    fn parse(index: usize, line: &str) -> Self {
+        let cells: Vec<Cell> = line.chars().map(Cell::from_char).collect();
+        let mut nums = Vec::new();
+        let mut current: Option<RowNum> = None;
+        for (col, &cell) in cells.iter().enumerate() {
+            match (cell, &mut current) {
+                (Cell::Digit(digit), Some(row_num)) => {
+                    row_num.num = row_num.num * 10 + digit;
+                    row_num.end = col;
+                }
+                (Cell::Digit(digit), None) => {
+                    current = Some(RowNum {
+                        start: col,
+                        end: col,
+                        num: digit,
+                    })
+                }
+                _ => nums.extend(current.take()),
+            }
+        }
+        nums.extend(current);
+        Self { index, cells, nums }
+    }
+
+    /// Whether a symbol is in this row anywhere from `start - 1` to `end + 1`.
+    fn has_symbol_near(&self, start: usize, end: usize) -> bool {
+        let end = (end + 1).min(self.cells.len() - 1);
+        self.cells[start.saturating_sub(1)..=end]
+            .iter()
+            .any(|cell| matches!(cell, Cell::Symbol(_)))
+    }
+
+    fn nums_near(&self, col: usize) -> impl Iterator<Item = u32> + '_ {
+        self.nums
+            .iter()
+            .filter(move |row_num| row_num.start <= col + 1 && col <= row_num.end + 1)
+            .map(|row_num| row_num.num)
+    }
+}
+
+/// Reports the parts and gears of the middle row, which can only touch the
+/// rows either side of it.
+fn scan_row(
+    above: Option<&Row>,
+    row: &Row,
+    below: Option<&Row>,
+    rule: &GearRule,
+    on_found: &mut impl FnMut(Found),
+) {
+    let window = || above.into_iter().chain([row]).chain(below);
+    for row_num in &row.nums {
+        if window().any(|near| near.has_symbol_near(row_num.start, row_num.end)) {
+            on_found(Found::Part {
+                row: row.index,
+                num: row_num.num,
+            });
+        }
+    }
+    for (col, &cell) in row.cells.iter().enumerate() {
+        let Cell::Symbol(symbol) = cell else {
+            continue;
+        };
+        if !rule.matches(symbol) {
+            continue;
+        }
+        let nums: Vec<u64> = window()
+            .flat_map(|near| near.nums_near(col))
+            .map(u64::from)
+            .collect();
+        if nums.len() == rule.neighbours {
+            let ratio = match rule.combine {
+                Combine::Product => nums.iter().product(),
+                Combine::Sum => nums.iter().sum(),
+            };
+            on_found(Found::Gear {
+                position: (row.index, col),
+                ratio,
+            });
+        }
+    }
+}
+
+/// Reads the schematic a row at a time, only ever holding the row being
+/// checked and the ones either side of it, so memory grows with the width and
+/// not the height. Parts and gears are reported as soon as the row after
+/// theirs has been read.
+pub fn stream(
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    rule: &GearRule,
+    mut on_found: impl FnMut(Found),
+) -> AocResult<()> {
+    let mut above: Option<Row> = None;
+    let mut current: Option<Row> = None;
+    for (index, line) in lines.enumerate() {
+        let next = Row::parse(index, &line?);
+        if let Some(row) = &current {
+            if next.cells.len() != row.cells.len() {
+                anyhow::bail!(
+                    "Line {} has {} columns, but the schematic has {}",
+                    index + 1,
+                    next.cells.len(),
+                    row.cells.len()
+                );
+            }
+            scan_row(above.as_ref(), row, Some(&next), rule, &mut on_found);
+        }
+        above = current.replace(next);
+    }
+    if let Some(row) = &current {
+        scan_row(above.as_ref(), row, None, rule, &mut on_found);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stream, Found};
+    use crate::impls::problem_3::{Board, GearRule};
+
+    #[test]
+    fn test_matches_the_board() {
+        let lines = [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ];
+        let to_lines = || lines.iter().map(|line| Ok(line.to_string()));
+        let board = Board::build_from_lines(to_lines()).unwrap();
+        let rule = GearRule::default();
+
+        let (mut parts, mut gears) = (0, 0);
+        stream(to_lines(), &rule, |found| match found {
+            Found::Part { num, .. } => parts += num as u64,
+            Found::Gear { ratio, .. } => gears += ratio,
+        })
+        .unwrap();
+        assert_eq!(parts, board.sum_parts());
+        assert_eq!(gears, board.calculate_gear_ratios(&rule));
+        assert_eq!((parts, gears), (4361, 467835));
+    }
+}
@@ -1,97 +1,202 @@
-use std::collections::HashSet;
 use std::collections::VecDeque;
 
-use crate::{AocResult, Part, Solution};
+use serde::Serialize;
 
-fn create_card_hashmap(line: &str) -> AocResult<HashSet<u32>> {
-    let mut winning_nums = HashSet::new();
-    for winning_num_str in line.split(' ') {
-        if winning_num_str.is_empty() {
-            continue;
+use crate::helpers::num_set::NumSet;
+use crate::{memory, AocResult, Part, Solution};
+
+pub mod graph;
+
+fn parse_nums<T: FromIterator<u32>>(nums: &str) -> AocResult<T> {
+    nums.split(' ')
+        .filter(|num| !num.is_empty())
+        .map(|num| Ok(num.parse()?))
+        .collect()
+}
+
+/// One scratchcard: its id, the winning numbers and the numbers on the card.
+/// My numbers keep any repeats, since each one counts as its own match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: NumSet,
+    pub mine: Vec<u32>,
+}
+
+impl Card {
+    /// Parses a `Card N: winning numbers | my numbers` line.
+    pub fn parse(line: &str) -> AocResult<Self> {
+        let (name, num_portion) = line
+            .split_once(':')
+            .ok_or(anyhow::anyhow!("Card has no `:`: {line}"))?;
+        let id = name
+            .strip_prefix("Card")
+            .ok_or(anyhow::anyhow!("Card doesn't start with `Card`: {line}"))?
+            .trim()
+            .parse()?;
+        let (winning_nums, my_nums) = num_portion
+            .split_once('|')
+            .ok_or(anyhow::anyhow!("Card has no `|`: {line}"))?;
+        Ok(Self {
+            id,
+            winning: parse_nums(winning_nums)?,
+            mine: parse_nums(my_nums)?,
+        })
+    }
+
+    /// How many of my numbers are winning numbers, counting repeats.
+    pub fn matches(&self) -> usize {
+        self.mine
+            .iter()
+            .filter(|&&num| self.winning.contains(num))
+            .count()
+    }
+
+    /// One point for the first match, doubled for each match after it. Cards
+    /// with more than 64 matches are worth `u64::MAX`.
+    pub fn points(&self) -> u64 {
+        match self.matches() {
+            0 => 0,
+            matches => u32::try_from(matches - 1)
+                .ok()
+                .and_then(|shift| 1u64.checked_shl(shift))
+                .unwrap_or(u64::MAX),
         }
-        let num = winning_num_str.parse()?;
-        winning_nums.insert(num);
     }
-    Ok(winning_nums)
 }
 
-fn create_winning_nums(line: &str) -> AocResult<Box<[u32]>> {
-    let my_nums = line.split(' ');
-    let mut my_num_vec = match my_nums.size_hint() {
-        (_, None) => Vec::new(),
-        (_, Some(size)) => Vec::with_capacity(size),
-    };
-    for num in my_nums {
-        if num.is_empty() {
-            continue;
+/// Every copy of card `from` winning a copy of card `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Grant {
+    pub from: u32,
+    pub to: u32,
+    pub copies: u64,
+}
+
+/// How the copies spread: each card's final count, by id, and every grant
+/// that added to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    pub counts: Vec<(u32, u64)>,
+    pub grants: Vec<Grant>,
+}
+
+/// Every card, in id order. Card `N` with `M` matches wins a copy of each
+/// card from `N + 1` to `N + M`; ids that aren't in the game win nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchcardGame {
+    cards: Vec<Card>,
+}
+
+impl ScratchcardGame {
+    /// Sorts the cards by id, which have to be unique.
+    pub fn new(mut cards: Vec<Card>) -> AocResult<Self> {
+        cards.sort_by_key(|card| card.id);
+        if let Some(pair) = cards.windows(2).find(|pair| pair[0].id == pair[1].id) {
+            anyhow::bail!("Card {} is listed twice", pair[0].id);
         }
-        let num = num.parse()?;
-        my_num_vec.push(num);
+        Ok(Self { cards })
     }
-    Ok(my_num_vec.into_boxed_slice())
-}
 
-fn solve_p1_part_1(
-    lines: impl Iterator<Item = Result<String, std::io::Error>>,
-) -> AocResult<Solution> {
-    let mut sum = 0;
-    for card_row in lines {
-        let card_row = card_row?;
-        let (_, num_portion) = card_row.split_once(':').ok_or(anyhow::anyhow!(
-            "AOC has invalid input!!?!??! Input: {card_row}"
-        ))?;
-        let (winning_nums, my_nums) = num_portion.split_once('|').ok_or(anyhow::anyhow!(
-            "AOC has invalid input?!?!?!?!?! Input: {num_portion}"
-        ))?;
-        let winning_num_set = create_card_hashmap(winning_nums)?;
-        let my_nums = create_winning_nums(&my_nums)?;
-        let mut row_sum = 0;
-        for &my_num in my_nums.iter() {
-            if winning_num_set.contains(&my_num) {
-                if row_sum == 0 {
-                    row_sum += 1;
-                } else {
-                    row_sum *= 2;
+    pub fn from_lines(
+        lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    ) -> AocResult<Self> {
+        let mut cards = Vec::new();
+        for line in lines {
+            cards.push(Card::parse(&line?)?);
+        }
+        Self::new(cards)
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn card(&self, id: u32) -> Option<&Card> {
+        self.cards
+            .binary_search_by_key(&id, |card| card.id)
+            .ok()
+            .map(|idx| &self.cards[idx])
+    }
+
+    /// Each card's id with its number of matches.
+    pub fn match_counts(&self) -> Vec<(u32, usize)> {
+        self.cards
+            .iter()
+            .map(|card| (card.id, card.matches()))
+            .collect()
+    }
+
+    pub fn points(&self) -> u64 {
+        self.cards
+            .iter()
+            .map(Card::points)
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Plays every card in id order, recording how many of each there are and
+    /// which cards won copies of which.
+    pub fn cascade(&self) -> Cascade {
+        let mut cascade = Cascade {
+            counts: Vec::with_capacity(self.cards.len()),
+            grants: Vec::new(),
+        };
+        // Copies won of the cards after the previous one, nearest first.
+        let mut look_forward_scratchcards: VecDeque<u64> = VecDeque::new();
+        let mut previous_id = None;
+        for card in &self.cards {
+            // Copies won of missing ids are lost.
+            let skipped = previous_id.map_or(0, |previous_id| card.id - previous_id - 1) as usize;
+            look_forward_scratchcards.drain(..skipped.min(look_forward_scratchcards.len()));
+            let copies = 1 + look_forward_scratchcards.pop_front().unwrap_or_default();
+            let total_wins = card.matches();
+            if look_forward_scratchcards.len() < total_wins {
+                look_forward_scratchcards.resize(total_wins, 0);
+            }
+            for (offset, scratchcard) in look_forward_scratchcards
+                .iter_mut()
+                .take(total_wins)
+                .enumerate()
+            {
+                *scratchcard += copies;
+                let to = card.id + 1 + offset as u32;
+                if self.card(to).is_some() {
+                    cascade.grants.push(Grant {
+                        from: card.id,
+                        to,
+                        copies,
+                    });
                 }
             }
+            cascade.counts.push((card.id, copies));
+            previous_id = Some(card.id);
         }
-        sum += row_sum;
+        cascade
+    }
+
+    /// Each card's id with how many of it there are once every won copy has
+    /// been scratched, the original included.
+    pub fn copy_counts(&self) -> Vec<(u32, u64)> {
+        self.cascade().counts
     }
-    Ok(Solution::Int(sum as i32))
+
+    pub fn total_cards(&self) -> u64 {
+        self.copy_counts().iter().map(|&(_, copies)| copies).sum()
+    }
+}
+
+fn solve_p1_part_1(
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+) -> AocResult<Solution> {
+    let game = memory::track("parse", || ScratchcardGame::from_lines(lines))?;
+    Ok(Solution::Int(game.points() as i64))
 }
 
 fn solve_p2_part_2(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
-    let mut sum = 0;
-    let mut look_forward_scratchcards: VecDeque<u32> = VecDeque::new();
-    for card_row in lines {
-        let card_row = card_row?;
-        let (_, num_portion) = card_row.split_once(':').ok_or(anyhow::anyhow!(
-            "AOC has invalid input!!?!??! Input: {card_row}"
-        ))?;
-        let (winning_nums, my_nums) = num_portion.split_once('|').ok_or(anyhow::anyhow!(
-            "AOC has invalid input?!?!?!?!?! Input: {num_portion}"
-        ))?;
-        let winning_num_set = create_card_hashmap(winning_nums)?;
-        let my_nums = create_winning_nums(&my_nums)?;
-        let cards = 1 + look_forward_scratchcards.pop_front().unwrap_or_default();
-        let mut total_wins = 0;
-        for my_num in my_nums.iter() {
-            if winning_num_set.contains(my_num) {
-                total_wins += 1;
-            }
-        }
-        let current_len = look_forward_scratchcards.len();
-        for i in 0..std::cmp::min(total_wins as usize, current_len) {
-            look_forward_scratchcards[i] += 1 * cards;
-        }
-        for _ in current_len..total_wins {
-            look_forward_scratchcards.push_back(1 * cards);
-        }
-        sum += cards;
-    }
-    Ok(Solution::Int(sum as i32))
+    let game = memory::track("parse", || ScratchcardGame::from_lines(lines))?;
+    Ok(Solution::Int(game.total_cards() as i64))
 }
 
 pub fn solve(
@@ -103,3 +208,47 @@ pub fn solve(
         Part::Two => solve_p2_part_2(lines),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Card, ScratchcardGame};
+
+    #[test]
+    fn test_out_of_order_and_missing_ids() {
+        let lines = [
+            "Card 4: 1 2 | 1 2",
+            "Card 1: 1 2 3 | 1 2 9",
+            "Card 2: 5 | 5",
+            "Card 7: 1 | 2",
+        ];
+        let game =
+            ScratchcardGame::from_lines(lines.iter().map(|line| Ok(line.to_string()))).unwrap();
+        assert_eq!(game.match_counts(), vec![(1, 2), (2, 1), (4, 2), (7, 0)]);
+        assert_eq!(game.points(), 2 + 1 + 2);
+        // Card 1 wins 2 and 3, but 3 is missing; card 2 wins 3 as well. Card 4
+        // wins 5 and 6, which are missing, so 7 only has its original.
+        assert_eq!(game.copy_counts(), vec![(1, 1), (2, 2), (4, 1), (7, 1)]);
+        assert_eq!(game.card(4).unwrap().mine, vec![1, 2]);
+        assert!(game.card(3).is_none());
+
+        let twice = ["Card 1: 1 | 1", "Card 1: 2 | 2"];
+        assert!(
+            ScratchcardGame::from_lines(twice.iter().map(|line| Ok(line.to_string()))).is_err()
+        );
+    }
+
+    #[test]
+    fn test_repeated_numbers_and_huge_scores() {
+        let card = Card::parse("Card 1: 5 6 | 5 5 7 6").unwrap();
+        assert_eq!((card.matches(), card.points()), (3, 4));
+
+        let card_with = |matches: u32| {
+            let nums: Vec<String> = (1..=matches).map(|num| num.to_string()).collect();
+            let nums = nums.join(" ");
+            Card::parse(&format!("Card 2: {nums} | {nums}")).unwrap()
+        };
+        assert_eq!(card_with(64).points(), 1 << 63);
+        assert_eq!(card_with(65).points(), u64::MAX);
+        assert_eq!(card_with(70).points(), u64::MAX);
+    }
+}
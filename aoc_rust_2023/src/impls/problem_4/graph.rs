@@ -0,0 +1,78 @@
+use super::ScratchcardGame;
+
+/// The copy cascade as a Graphviz digraph. Each card is labelled with its
+/// matches and final count, and each edge with the copies it contributed.
+pub fn to_dot(game: &ScratchcardGame) -> String {
+    let cascade = game.cascade();
+    let mut out = String::from("digraph cascade {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (card, &(id, copies)) in game.cards().iter().zip(&cascade.counts) {
+        out.push_str(&format!(
+            "    {id} [label=\"Card {id}\\n{} matches\\n{copies} copies\"];\n",
+            card.matches()
+        ));
+    }
+    for grant in &cascade.grants {
+        out.push_str(&format!(
+            "    {} -> {} [label=\"{}\", weight={}];\n",
+            grant.from, grant.to, grant.copies, grant.copies
+        ));
+    }
+    out.push_str(&format!(
+        "    label=\"{} scratchcards\";\n}}\n",
+        cascade
+            .counts
+            .iter()
+            .map(|&(_, copies)| copies)
+            .sum::<u64>()
+    ));
+    out
+}
+
+/// The same graph as `to_dot`, as JSON with `cards`, `grants` and `total`.
+pub fn to_json(game: &ScratchcardGame) -> serde_json::Value {
+    let cascade = game.cascade();
+    let cards: Vec<_> = game
+        .cards()
+        .iter()
+        .zip(&cascade.counts)
+        .map(|(card, &(id, copies))| {
+            serde_json::json!({
+                "id": id,
+                "matches": card.matches(),
+                "copies": copies,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "cards": cards,
+        "grants": cascade.grants,
+        "total": cascade.counts.iter().map(|&(_, copies)| copies).sum::<u64>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_dot, to_json};
+    use crate::impls::problem_4::ScratchcardGame;
+
+    #[test]
+    fn test_graph_matches_the_copy_counts() {
+        let lines = ["Card 1: 1 2 | 1 2", "Card 2: 3 | 3", "Card 3: 4 | 5"];
+        let game =
+            ScratchcardGame::from_lines(lines.iter().map(|line| Ok(line.to_string()))).unwrap();
+        let json = to_json(&game);
+        assert_eq!(json["total"], game.total_cards());
+        assert_eq!(json["total"], 1 + 2 + 4);
+        assert_eq!(
+            json["grants"],
+            serde_json::json!([
+                {"from": 1, "to": 2, "copies": 1},
+                {"from": 1, "to": 3, "copies": 1},
+                {"from": 2, "to": 3, "copies": 2},
+            ])
+        );
+        let dot = to_dot(&game);
+        assert!(dot.contains("    2 -> 3 [label=\"2\", weight=2];\n"));
+        assert!(dot.contains("Card 3\\n0 matches\\n4 copies"));
+    }
+}
@@ -1,38 +1,51 @@
-use crate::{AocResult, Part, Solution};
-
-#[derive(Debug, Clone, Copy)]
-enum MapType {
-    SeedToSoil,
-    SoilToFertilizer,
-    FertilizerToWater,
-    WaterToLight,
-    LightToTemperature,
-    TemperatureToHumidity,
-    HumidityToLocation,
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::config::Params;
+use crate::{memory, AocResult, Part, Solution};
+
+pub mod piecewise;
+pub mod validate;
+
+use piecewise::Piecewise;
+
+/// A `source-to-target` map, like `seed-to-soil`. Together they make a graph
+/// of categories with a map for each edge.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MapType {
+    pub source: String,
+    pub target: String,
 }
 
 impl TryFrom<&str> for MapType {
     type Error = anyhow::Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "seed-to-soil" => Ok(Self::SeedToSoil),
-            "soil-to-fertilizer" => Ok(Self::SoilToFertilizer),
-            "fertilizer-to-water" => Ok(Self::FertilizerToWater),
-            "water-to-light" => Ok(Self::WaterToLight),
-            "light-to-temperature" => Ok(Self::LightToTemperature),
-            "temperature-to-humidity" => Ok(Self::TemperatureToHumidity),
-            "humidity-to-location" => Ok(Self::HumidityToLocation),
+        match value.split_once("-to-") {
+            Some((source, target)) if !source.is_empty() && !target.is_empty() => Ok(Self {
+                source: source.to_string(),
+                target: target.to_string(),
+            }),
             _ => Err(anyhow::anyhow!("Value={value} is not a valid map type")),
         }
     }
 }
 
+impl std::fmt::Display for MapType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-to-{}", self.source, self.target)
+    }
+}
+
+pub const SEED: &str = "seed";
+pub const LOCATION: &str = "location";
+
 #[derive(Debug)]
 struct Range {
     origin_start: u64,
     origin_end: u64,
     offset: i64,
+    destination_start: u64,
+    length: u64,
 }
 
 impl TryFrom<&str> for Range {
@@ -45,6 +58,8 @@ impl TryFrom<&str> for Range {
         for c in value.chars() {
             if c.is_ascii_digit() {
                 num_str.push(c);
+            } else if c != ' ' {
+                anyhow::bail!("Value={value} has something other than nums and spaces");
             } else {
                 if num_idx >= nums.len() {
                     anyhow::bail!("Value={value} has more than three nums");
@@ -55,16 +70,24 @@ impl TryFrom<&str> for Range {
                 num_str = String::new();
             }
         }
+        if num_idx == nums.len() {
+            anyhow::bail!("Value={value} has more than three nums");
+        }
         let num = num_str.parse()?;
         nums[num_idx] = num;
         num_idx += 1;
         if num_idx != nums.len() {
             anyhow::bail!("Value={value} has fewer than three nums");
         }
+        // Anything that doesn't fit is clamped here and reported by the
+        // validator, rather than failing the parse.
+        let offset = nums[0] as i128 - nums[1] as i128;
         Ok(Self {
             origin_start: nums[1],
-            origin_end: nums[1] + nums[2],
-            offset: nums[0] as i64 - nums[1] as i64,
+            origin_end: nums[1].saturating_add(nums[2]),
+            offset: offset.clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+            destination_start: nums[0],
+            length: nums[2],
         })
     }
 }
@@ -77,60 +100,150 @@ impl Range {
     fn map_from(&self, value: u64) -> u64 {
         value.saturating_add_signed(self.offset)
     }
+
+    /// Whether either end runs past `u64::MAX` or the shift doesn't fit an
+    /// `i64`.
+    fn overflows(&self) -> bool {
+        self.origin_start.checked_add(self.length).is_none()
+            || self.destination_start.checked_add(self.length).is_none()
+            || i64::try_from(self.destination_start as i128 - self.origin_start as i128).is_err()
+    }
 }
 
-#[derive(Debug)]
-struct ALotOfMaps {
-    seed_to_soil: Vec<Range>,
-    soil_to_fertilizer: Vec<Range>,
-    fertilizer_to_water: Vec<Range>,
-    water_to_light: Vec<Range>,
-    light_to_temperature: Vec<Range>,
-    temperature_to_humidity: Vec<Range>,
-    humidity_to_location: Vec<Range>,
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.destination_start, self.origin_start, self.length
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ALotOfMaps {
+    maps: BTreeMap<MapType, Vec<Range>>,
 }
 
 impl ALotOfMaps {
     fn new() -> Self {
-        Self {
-            seed_to_soil: vec![],
-            soil_to_fertilizer: vec![],
-            fertilizer_to_water: vec![],
-            water_to_light: vec![],
-            light_to_temperature: vec![],
-            temperature_to_humidity: vec![],
-            humidity_to_location: vec![],
-        }
+        Self::default()
     }
 
-    fn add_range(&mut self, range: Range, map_type: MapType) {
-        let map_pointer = match map_type {
-            MapType::SeedToSoil => &mut self.seed_to_soil,
-            MapType::SoilToFertilizer => &mut self.soil_to_fertilizer,
-            MapType::FertilizerToWater => &mut self.fertilizer_to_water,
-            MapType::WaterToLight => &mut self.water_to_light,
-            MapType::LightToTemperature => &mut self.light_to_temperature,
-            MapType::TemperatureToHumidity => &mut self.temperature_to_humidity,
-            MapType::HumidityToLocation => &mut self.humidity_to_location,
-        };
-        map_pointer.push(range);
+    fn add_range(&mut self, range: Range, map_type: &MapType) {
+        self.maps.entry(map_type.clone()).or_default().push(range);
     }
 
     fn sort_ranges(&mut self) {
-        self.seed_to_soil
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.soil_to_fertilizer
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.fertilizer_to_water
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.water_to_light
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.light_to_temperature
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.temperature_to_humidity
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
-        self.humidity_to_location
-            .sort_by(|r1, r2| r1.origin_start.cmp(&r2.origin_start));
+        for ranges in self.maps.values_mut() {
+            ranges.sort_by_key(|r| r.origin_start);
+        }
+    }
+
+    pub fn map_types(&self) -> impl Iterator<Item = &MapType> + '_ {
+        self.maps.keys()
+    }
+
+    /// Every category that is the source or target of a map.
+    pub fn categories(&self) -> BTreeSet<&str> {
+        self.maps
+            .keys()
+            .flat_map(|map_type| [map_type.source.as_str(), map_type.target.as_str()])
+            .collect()
+    }
+
+    fn ranges(&self, map_type: &MapType) -> &[Range] {
+        self.maps.get(map_type).map_or(&[], Vec::as_slice)
+    }
+
+    /// The shortest chain of maps leading from the `from` category to `to`.
+    pub fn path(&self, from: &str, to: &str) -> AocResult<Vec<&MapType>> {
+        let mut reached_by: BTreeMap<&str, Option<&MapType>> = BTreeMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while let Some(Some(map_type)) = reached_by.get(current) {
+                    path.push(*map_type);
+                    current = &map_type.source;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for map_type in self
+                .maps
+                .keys()
+                .filter(|map_type| map_type.source == category)
+            {
+                if !reached_by.contains_key(map_type.target.as_str()) {
+                    reached_by.insert(&map_type.target, Some(map_type));
+                    queue.push_back(&map_type.target);
+                }
+            }
+        }
+        anyhow::bail!("No maps lead from {from} to {to}")
+    }
+
+    /// Every category that can be reached from `from`, including itself.
+    pub fn reachable_from<'a>(&'a self, from: &'a str) -> BTreeSet<&'a str> {
+        let mut reached = BTreeSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            for map_type in self
+                .maps
+                .keys()
+                .filter(|map_type| map_type.source == category)
+            {
+                if reached.insert(&map_type.target) {
+                    queue.push_back(&map_type.target);
+                }
+            }
+        }
+        reached
+    }
+
+    /// The ranges of each map along the path from `from` to `to`.
+    fn stages(&self, from: &str, to: &str) -> AocResult<Vec<&[Range]>> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .map(|map_type| self.ranges(map_type))
+            .collect())
+    }
+
+    /// One map as a mapping that can be composed or inverted. Maps with
+    /// overlapping or overflowing ranges are refused, since the lookups in
+    /// `convert` would disagree with the composed mapping on them.
+    pub fn stage(&self, map_type: &MapType) -> AocResult<Piecewise> {
+        let ranges = self.ranges(map_type);
+        if let Some(range) = ranges.iter().find(|range| range.overflows()) {
+            anyhow::bail!("{map_type}: `{range}` overflows, run `almanac --validate` for details");
+        }
+        let sized: Vec<&Range> = ranges.iter().filter(|range| range.length > 0).collect();
+        let overlaps =
+            validate::overlapping(&sized, |range| (range.origin_start, range.origin_end));
+        if let Some((first, second)) = overlaps.first() {
+            anyhow::bail!(
+                "{map_type}: `{first}` and `{second}` share sources, run `almanac --validate` for details"
+            );
+        }
+        Ok(Piecewise::from_ranges(ranges))
+    }
+
+    /// Every map on the path from `from` to `to` composed into one mapping.
+    pub fn composite(&self, from: &str, to: &str) -> AocResult<Piecewise> {
+        let mut composite = Piecewise::identity();
+        for map_type in self.path(from, to)? {
+            composite = composite.then(&self.stage(map_type)?);
+        }
+        Ok(composite)
+    }
+
+    /// Converts a `from` value into a `to` value, through whichever
+    /// categories lie between them.
+    pub fn convert(&self, from: &str, to: &str, value: u64) -> AocResult<u64> {
+        Ok(Self::map_through(&self.stages(from, to)?, value))
     }
 
     fn calculate_next_value(ranges: &[Range], value: u64) -> u64 {
@@ -147,29 +260,73 @@ impl ALotOfMaps {
                 if range.contains(value) {
                     return range.map_from(value);
                 }
-                return value;
+                value
             }
         }
     }
 
-    fn map_seed_to_location(&self, seed: u64) -> u64 {
-        // println!("Evaluating seed {seed}");
-        let soil = Self::calculate_next_value(&self.seed_to_soil, seed);
-        // println!("seed: {seed}, soil: {soil}");
-        let fertilizer = Self::calculate_next_value(&self.soil_to_fertilizer, soil);
-        // println!("soil: {soil}, fertilizer: {fertilizer}");
-        let water = Self::calculate_next_value(&self.fertilizer_to_water, fertilizer);
-        // println!("fertilizer: {fertilizer}, water: {water}");
-        let light = Self::calculate_next_value(&self.water_to_light, water);
-        // println!("water: {water}, light: {light}");
-        let temp = Self::calculate_next_value(&self.light_to_temperature, light);
-        // println!("light: {light}, temp: {temp}");
-        let humidity = Self::calculate_next_value(&self.temperature_to_humidity, temp);
-        // println!("temp: {temp}, humidity: {humidity}");
-        let location = Self::calculate_next_value(&self.humidity_to_location, humidity);
-        // println!("humidity: {humidity}, location: {location}");
-        location
+    fn map_through(stages: &[&[Range]], value: u64) -> u64 {
+        stages.iter().fold(value, |value, ranges| {
+            Self::calculate_next_value(ranges, value)
+        })
     }
+
+    /// Pushes the half open `intervals` through one stage, splitting them
+    /// wherever they cross the edge of a range. Values outside every range
+    /// map to themselves.
+    fn calculate_next_intervals(ranges: &[Range], intervals: &[(u64, u64)]) -> Vec<(u64, u64)> {
+        let mut mapped = Vec::new();
+        for &(start, end) in intervals {
+            let mut cursor = start;
+            let first = ranges.partition_point(|r| r.origin_end <= cursor);
+            for range in &ranges[first..] {
+                if range.origin_start >= end {
+                    break;
+                }
+                if cursor < range.origin_start {
+                    mapped.push((cursor, range.origin_start));
+                    cursor = range.origin_start;
+                }
+                let overlap_end = end.min(range.origin_end);
+                if cursor < overlap_end {
+                    mapped.push((range.map_from(cursor), range.map_from(overlap_end)));
+                    cursor = overlap_end;
+                }
+            }
+            if cursor < end {
+                mapped.push((cursor, end));
+            }
+        }
+        merge_intervals(mapped)
+    }
+
+    /// The location intervals the seed intervals end up in, sorted and merged.
+    fn map_seed_intervals_to_locations(
+        &self,
+        seeds: Vec<(u64, u64)>,
+    ) -> AocResult<Vec<(u64, u64)>> {
+        Ok(self
+            .stages(SEED, LOCATION)?
+            .into_iter()
+            .fold(merge_intervals(seeds), |intervals, ranges| {
+                Self::calculate_next_intervals(ranges, &intervals)
+            }))
+    }
+}
+
+/// Sorts half open intervals and joins any that overlap or touch, so each
+/// stage carries at most one interval per gap between ranges.
+fn merge_intervals(mut intervals: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    intervals.retain(|&(start, end)| start < end);
+    intervals.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
 }
 
 fn create_seed_list(seed_line: &str, seeds_as_ranges: bool) -> AocResult<Vec<u64>> {
@@ -201,7 +358,7 @@ fn populate_seeds_and_maps(
     seeds_as_ranges: bool,
 ) -> AocResult<(Vec<u64>, ALotOfMaps)> {
     let mut seeds = Vec::new();
-    let mut map_type = MapType::FertilizerToWater;
+    let mut map_type = None;
     let mut lots_o_maps = ALotOfMaps::new();
     for line in lines {
         let line = line?;
@@ -215,48 +372,205 @@ fn populate_seeds_and_maps(
         let first_char = line.chars().next().unwrap();
         if first_char.is_ascii_digit() {
             let range = Range::try_from(line.as_str())?;
+            let map_type = map_type
+                .as_ref()
+                .ok_or(anyhow::anyhow!("Range={line} comes before any map"))?;
             lots_o_maps.add_range(range, map_type);
         } else {
             let (map_type_str, _) = line.split_once(' ').unwrap();
-            map_type = MapType::try_from(map_type_str)?;
+            map_type = Some(MapType::try_from(map_type_str)?);
         }
     }
     lots_o_maps.sort_ranges();
     Ok((seeds, lots_o_maps))
 }
 
+/// The seeds, read as plain numbers, and the maps.
+pub fn parse_almanac(
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+) -> AocResult<(Vec<u64>, ALotOfMaps)> {
+    populate_seeds_and_maps(lines, false)
+}
+
 fn solve_p1_part_1(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
     let mut lowest_location: u64 = u64::MAX;
-    let (seeds, lots_o_maps) = populate_seeds_and_maps(lines, false)?;
+    let (seeds, lots_o_maps) = memory::track("parse", || populate_seeds_and_maps(lines, false))?;
+    let stages = lots_o_maps.stages(SEED, LOCATION)?;
     for seed in seeds {
-        let location = lots_o_maps.map_seed_to_location(seed);
+        let location = ALotOfMaps::map_through(&stages, seed);
         // .ok_or(anyhow::anyhow!("Seed={seed} has no location"))?;
         lowest_location = std::cmp::min(lowest_location, location);
     }
-    Ok(Solution::Int(lowest_location as i32))
+    Ok(Solution::Int(lowest_location as i64))
 }
 
-fn solve_p2_part_2(
+/// How part 2 handles the seed ranges, from the `seed_ranges` parameter:
+/// `intervals` (the default) pushes each range through the maps whole, `brute`
+/// maps every single seed and is only there to check it against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedRanges {
+    Intervals,
+    Brute,
+}
+
+impl SeedRanges {
+    fn from_params(params: &Params) -> AocResult<Self> {
+        match params.get("seed_ranges") {
+            None | Some("intervals") => Ok(Self::Intervals),
+            Some("brute") => Ok(Self::Brute),
+            Some(other) => anyhow::bail!("Seed_ranges={other} must be intervals or brute"),
+        }
+    }
+}
+
+fn solve_p2_brute(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
     let mut lowest_location: u64 = u64::MAX;
-    let (seeds, lots_o_maps) = populate_seeds_and_maps(lines, true)?;
+    let (seeds, lots_o_maps) = memory::track("parse", || populate_seeds_and_maps(lines, true))?;
+    let stages = lots_o_maps.stages(SEED, LOCATION)?;
     for seed in seeds {
-        let location = lots_o_maps.map_seed_to_location(seed);
+        let location = ALotOfMaps::map_through(&stages, seed);
         lowest_location = std::cmp::min(lowest_location, location);
     }
-    println!("Actual solution in case it overflows: {lowest_location}");
-    Ok(Solution::Int(lowest_location as i32))
+    Ok(Solution::Int(lowest_location as i64))
+}
+
+fn solve_p2_part_2(
+    lines: impl Iterator<Item = Result<String, std::io::Error>>,
+) -> AocResult<Solution> {
+    let (seeds, lots_o_maps) = memory::track("parse", || populate_seeds_and_maps(lines, false))?;
+    if seeds.len() % 2 != 0 {
+        anyhow::bail!("Seeds={seeds:?} are not start and length pairs");
+    }
+    let seed_intervals = seeds
+        .chunks(2)
+        .map(|pair| (pair[0], pair[0] + pair[1]))
+        .collect();
+    let locations = lots_o_maps.map_seed_intervals_to_locations(seed_intervals)?;
+    let (lowest_location, _) = locations
+        .first()
+        .ok_or(anyhow::anyhow!("There are no seeds"))?;
+    Ok(Solution::Int(*lowest_location as i64))
 }
 
 pub fn solve(
     part: Part,
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
+    params: &Params,
 ) -> AocResult<Solution> {
     match part {
         Part::One => solve_p1_part_1(lines),
-        Part::Two => solve_p2_part_2(lines),
+        Part::Two => match SeedRanges::from_params(params)? {
+            SeedRanges::Intervals => solve_p2_part_2(lines),
+            SeedRanges::Brute => solve_p2_brute(lines),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{populate_seeds_and_maps, ALotOfMaps, Range, LOCATION, SEED};
+
+    #[test]
+    fn test_intervals_match_brute_force() {
+        let almanac = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\
+            soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n\n\
+            fertilizer-to-water map:\n49 53 8\n0 11 42\n42 0 7\n57 7 4\n\n\
+            water-to-light map:\n88 18 7\n18 25 70\n\n\
+            light-to-temperature map:\n45 77 23\n81 45 19\n68 64 13\n\n\
+            temperature-to-humidity map:\n0 69 1\n1 0 69\n\n\
+            humidity-to-location map:\n60 56 37\n56 93 4";
+        let lines = || almanac.lines().map(|line| Ok(line.to_string()));
+        let (seeds, maps) = populate_seeds_and_maps(lines(), false).unwrap();
+        let intervals = seeds
+            .chunks(2)
+            .map(|pair| (pair[0], pair[0] + pair[1]))
+            .collect();
+        let locations = maps.map_seed_intervals_to_locations(intervals).unwrap();
+
+        let (all_seeds, _) = populate_seeds_and_maps(lines(), true).unwrap();
+        let mut brute: Vec<u64> = all_seeds
+            .into_iter()
+            .map(|seed| maps.convert(SEED, LOCATION, seed).unwrap())
+            .collect();
+        brute.sort_unstable();
+        let expanded: Vec<u64> = locations
+            .iter()
+            .flat_map(|&(start, end)| start..end)
+            .collect();
+        assert_eq!(expanded, brute);
+        assert_eq!(locations[0].0, 46);
+
+        let composite = maps.composite(SEED, LOCATION).unwrap();
+        for seed in 0..120 {
+            let location = maps.convert(SEED, LOCATION, seed).unwrap();
+            assert_eq!(composite.apply(seed), location);
+            assert!(composite.preimage(location).contains(&seed));
+        }
+        assert_eq!(composite.preimage(35), vec![13]);
+    }
+
+    #[test]
+    fn test_composing_refuses_overlapping_ranges() {
+        // 5 is in both ranges: the lookup finds `20 3 1` and leaves it alone,
+        // so no single rule would agree with it for every value.
+        let almanac = "seeds: 5
+
+seed-to-location map:
+100 0 10
+20 3 1";
+        let (_, maps) =
+            populate_seeds_and_maps(almanac.lines().map(|line| Ok(line.to_string())), false)
+                .unwrap();
+        assert_eq!(maps.convert(SEED, LOCATION, 5).unwrap(), 5);
+        let error = maps.composite(SEED, LOCATION).unwrap_err().to_string();
+        assert!(error.starts_with("seed-to-location: `100 0 10` and `20 3 1` share sources"));
+    }
+
+    #[test]
+    fn test_any_categories_in_any_order() {
+        let almanac = "seeds: 1\n\nlocation-to-weather map:\n100 0 10\n\n\
+            soil-to-water map:\n0 0 1\n\nseed-to-soil map:\n5 0 10\n\n\
+            soil-to-location map:\n0 5 3";
+        let (_, maps) =
+            populate_seeds_and_maps(almanac.lines().map(|line| Ok(line.to_string())), false)
+                .unwrap();
+        let path: Vec<String> = maps
+            .path(SEED, "weather")
+            .unwrap()
+            .iter()
+            .map(|map_type| map_type.to_string())
+            .collect();
+        assert_eq!(
+            path,
+            vec!["seed-to-soil", "soil-to-location", "location-to-weather"]
+        );
+        assert_eq!(maps.convert(SEED, "weather", 1).unwrap(), 101);
+        assert!(maps.convert("weather", SEED, 1).is_err());
+        assert_eq!(maps.categories().len(), 5);
+    }
+
+    #[test]
+    fn test_range_needs_exactly_three_nums() {
+        assert!(Range::try_from("1 2 3").is_ok());
+        for bad in ["1 2 3 4", "1 2", "1,2,3", "1 2 x"] {
+            assert!(Range::try_from(bad).is_err(), "{bad} parsed");
+        }
+    }
+
+    #[test]
+    fn test_intervals_split_at_range_edges() {
+        let ranges = [
+            Range::try_from("100 10 5").unwrap(),
+            Range::try_from("0 20 5").unwrap(),
+        ];
+        let mapped = ALotOfMaps::calculate_next_intervals(&ranges, &[(8, 22), (30, 31)]);
+        assert_eq!(
+            mapped,
+            vec![(0, 2), (8, 10), (15, 20), (30, 31), (100, 105)]
+        );
     }
 }
@@ -0,0 +1,172 @@
+use super::Range;
+
+/// Values from `start` up to (not including) `end` move by `offset`. Offsets
+/// are `i128` so that composing two `i64` shifts can't overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub start: u64,
+    pub end: u64,
+    pub offset: i128,
+}
+
+impl Piece {
+    fn shift(&self, value: u64) -> u64 {
+        (value as i128 + self.offset).clamp(0, u64::MAX as i128) as u64
+    }
+}
+
+/// A mapping of every `u64` as sorted pieces that each shift their values by
+/// a fixed amount. Unlike a single stage's ranges there are no gaps: values no
+/// range covers are in pieces with no offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piecewise {
+    pieces: Vec<Piece>,
+}
+
+impl Piecewise {
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![Piece {
+                start: 0,
+                end: u64::MAX,
+                offset: 0,
+            }],
+        }
+    }
+
+    /// One stage's ranges, which must be sorted by `origin_start` and must
+    /// not overlap or overflow.
+    pub(super) fn from_ranges(ranges: &[Range]) -> Self {
+        let mut pieces = Vec::with_capacity(ranges.len() * 2 + 1);
+        let mut cursor = 0;
+        for range in ranges {
+            let start = range.origin_start.max(cursor);
+            if start >= range.origin_end {
+                continue;
+            }
+            if cursor < start {
+                pieces.push(Piece {
+                    start: cursor,
+                    end: start,
+                    offset: 0,
+                });
+            }
+            pieces.push(Piece {
+                start,
+                end: range.origin_end,
+                offset: range.offset as i128,
+            });
+            cursor = range.origin_end;
+        }
+        if cursor < u64::MAX {
+            pieces.push(Piece {
+                start: cursor,
+                end: u64::MAX,
+                offset: 0,
+            });
+        }
+        Self::merged(pieces)
+    }
+
+    /// Joins neighbouring pieces that shift by the same amount.
+    fn merged(pieces: Vec<Piece>) -> Self {
+        let mut merged: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            match merged.last_mut() {
+                Some(last) if last.end == piece.start && last.offset == piece.offset => {
+                    last.end = piece.end
+                }
+                _ => merged.push(piece),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        let idx = self.pieces.partition_point(|piece| piece.end <= value);
+        match self.pieces.get(idx) {
+            Some(piece) if piece.start <= value => piece.shift(value),
+            _ => value,
+        }
+    }
+
+    /// This mapping followed by `next`, as a single mapping.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+        for piece in &self.pieces {
+            // Where this piece's values land, which `next` may split further.
+            let image_start = piece.start as i128 + piece.offset;
+            let image_end = piece.end as i128 + piece.offset;
+            for next_piece in &next.pieces {
+                let start = image_start.max(next_piece.start as i128);
+                let end = image_end.min(next_piece.end as i128);
+                if start >= end {
+                    continue;
+                }
+                pieces.push(Piece {
+                    start: (start - piece.offset) as u64,
+                    end: (end - piece.offset) as u64,
+                    offset: piece.offset + next_piece.offset,
+                });
+            }
+        }
+        pieces.sort_unstable_by_key(|piece| piece.start);
+        Self::merged(pieces)
+    }
+
+    /// Every value that maps to `value`, in ascending order. There can be none
+    /// or several if the ranges send more than one source to the same place.
+    pub fn preimage(&self, value: u64) -> Vec<u64> {
+        self.pieces
+            .iter()
+            .filter_map(|piece| {
+                let source = value as i128 - piece.offset;
+                (piece.start as i128 <= source && source < piece.end as i128)
+                    .then_some(source as u64)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Piecewise;
+    use crate::impls::problem_5::Range;
+
+    #[test]
+    fn test_composition_and_preimage() {
+        // Moves 10..15 up to 100..105, then 100..103 down to 0..3.
+        let first = Piecewise::from_ranges(&[Range::try_from("100 10 5").unwrap()]);
+        let second = Piecewise::from_ranges(&[Range::try_from("0 100 3").unwrap()]);
+        let both = first.then(&second);
+        for value in [0, 9, 10, 12, 13, 14, 15, 100, 102, 103, 1000] {
+            assert_eq!(both.apply(value), second.apply(first.apply(value)));
+        }
+        assert_eq!(both.apply(11), 1);
+        assert_eq!(both.preimage(1), vec![1, 11, 101]);
+        assert_eq!(both.preimage(101), Vec::<u64>::new());
+        assert_eq!(both.preimage(104), vec![14, 104]);
+        assert_eq!(Piecewise::identity().then(&first), first);
+    }
+
+    #[test]
+    fn test_composed_offsets_can_leave_the_i64_range() {
+        let up = Piecewise::from_ranges(&[Range::try_from("9223372036854775807 0 10").unwrap()]);
+        let up_again = Piecewise::from_ranges(&[Range::try_from(
+            "18446744073709551610 9223372036854775807 5",
+        )
+        .unwrap()]);
+        let both = up.then(&up_again);
+        assert_eq!(both.apply(0), 18446744073709551610);
+        assert_eq!(both.apply(4), 18446744073709551614);
+        // Values the first stage leaves alone can land there too.
+        assert_eq!(
+            both.preimage(18446744073709551612),
+            vec![2, 9223372036854775809, 18446744073709551612]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use super::{ALotOfMaps, MapType, Range, SEED};
+
+/// Something in an almanac that the solvers would quietly work around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// Two ranges share source values, so which one applies depends on the
+    /// order they're searched in.
+    Overlap {
+        map_type: MapType,
+        first: String,
+        second: String,
+    },
+    ZeroLength {
+        map_type: MapType,
+        range: String,
+    },
+    /// A range runs past `u64::MAX` at either end, or shifts by more than an
+    /// `i64` holds.
+    Overflow {
+        map_type: MapType,
+        range: String,
+    },
+    /// Two ranges send values to the same place, so the map isn't one to one.
+    Collision {
+        map_type: MapType,
+        first: String,
+        second: String,
+    },
+    /// No chain of maps leads from seeds to this category.
+    Unreachable {
+        category: String,
+    },
+}
+
+impl Issue {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Overlap { .. } => "overlap",
+            Self::ZeroLength { .. } => "zero_length",
+            Self::Overflow { .. } => "overflow",
+            Self::Collision { .. } => "collision",
+            Self::Unreachable { .. } => "unreachable",
+        }
+    }
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overlap {
+                map_type,
+                first,
+                second,
+            } => write!(f, "{map_type}: `{first}` and `{second}` share sources"),
+            Self::ZeroLength { map_type, range } => write!(f, "{map_type}: `{range}` is empty"),
+            Self::Overflow { map_type, range } => write!(f, "{map_type}: `{range}` overflows"),
+            Self::Collision {
+                map_type,
+                first,
+                second,
+            } => write!(f, "{map_type}: `{first}` and `{second}` share destinations"),
+            Self::Unreachable { category } => write!(f, "{category} can't be reached from {SEED}"),
+        }
+    }
+}
+
+/// Pairs of ranges whose `[start, end)` spans overlap, each reported against
+/// the earlier range reaching furthest.
+pub(super) fn overlapping<'a>(
+    ranges: &[&'a Range],
+    span: impl Fn(&Range) -> (u64, u64),
+) -> Vec<(&'a Range, &'a Range)> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_by_key(|range| span(range));
+    let mut overlaps = Vec::new();
+    let mut furthest: Option<&Range> = None;
+    for range in sorted {
+        let (start, end) = span(range);
+        if let Some(previous) = furthest {
+            if start < span(previous).1 {
+                overlaps.push((previous, range));
+            }
+        }
+        if furthest.is_none_or(|previous| end > span(previous).1) {
+            furthest = Some(range);
+        }
+    }
+    overlaps
+}
+
+/// Checks every map for overlapping, empty, overflowing and colliding ranges,
+/// then the category graph for categories seeds never reach.
+pub fn validate(maps: &ALotOfMaps) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (map_type, ranges) in &maps.maps {
+        let mut sized = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if range.length == 0 {
+                issues.push(Issue::ZeroLength {
+                    map_type: map_type.clone(),
+                    range: range.to_string(),
+                });
+            } else if range.overflows() {
+                issues.push(Issue::Overflow {
+                    map_type: map_type.clone(),
+                    range: range.to_string(),
+                });
+            } else {
+                sized.push(range);
+            }
+        }
+        for (first, second) in overlapping(&sized, |range| (range.origin_start, range.origin_end)) {
+            issues.push(Issue::Overlap {
+                map_type: map_type.clone(),
+                first: first.to_string(),
+                second: second.to_string(),
+            });
+        }
+        let destination = |range: &Range| {
+            (
+                range.destination_start,
+                range.destination_start + range.length,
+            )
+        };
+        for (first, second) in overlapping(&sized, destination) {
+            issues.push(Issue::Collision {
+                map_type: map_type.clone(),
+                first: first.to_string(),
+                second: second.to_string(),
+            });
+        }
+    }
+    let reachable = maps.reachable_from(SEED);
+    for category in maps.categories() {
+        if !reachable.contains(category) {
+            issues.push(Issue::Unreachable {
+                category: category.to_string(),
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use crate::impls::problem_5::populate_seeds_and_maps;
+
+    #[test]
+    fn test_reports_every_kind_of_issue() {
+        let almanac = "seeds: 1\n\nseed-to-soil map:\n50 0 10\n55 5 10\n0 20 0\n\
+            18446744073709551615 30 2\n\nsoil-to-location map:\n0 0 10\n5 20 10\n\n\
+            weather-to-location map:\n0 0 1";
+        let (_, maps) =
+            populate_seeds_and_maps(almanac.lines().map(|line| Ok(line.to_string())), false)
+                .unwrap();
+        let issues: Vec<String> = validate(&maps)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "seed-to-soil: `0 20 0` is empty",
+                "seed-to-soil: `18446744073709551615 30 2` overflows",
+                "seed-to-soil: `50 0 10` and `55 5 10` share sources",
+                "seed-to-soil: `50 0 10` and `55 5 10` share destinations",
+                "soil-to-location: `0 0 10` and `5 20 10` share destinations",
+                "weather can't be reached from seed",
+            ]
+        );
+    }
+}
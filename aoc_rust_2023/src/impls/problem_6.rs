@@ -1,4 +1,4 @@
-use crate::{AocResult, Part, Solution};
+use crate::{memory, AocResult, Part, Solution};
 
 struct RaceParams {
     time: u64,
@@ -76,22 +76,22 @@ fn solve_p1_part_1(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
     let mut solution = 1;
-    let race_params = lines_to_race_params(lines, false)?;
+    let race_params = memory::track("parse", || lines_to_race_params(lines, false))?;
     for race in race_params {
         solution *= race.num_ways_to_solve();
     }
-    Ok(Solution::Int(solution as i32))
+    Ok(Solution::Int(solution as i64))
 }
 
 fn solve_p2_part_2(
     lines: impl Iterator<Item = Result<String, std::io::Error>>,
 ) -> AocResult<Solution> {
     let mut solution = 1;
-    let race_params = lines_to_race_params(lines, true)?;
+    let race_params = memory::track("parse", || lines_to_race_params(lines, true))?;
     for race in race_params {
         solution *= race.num_ways_to_solve();
     }
-    Ok(Solution::Int(solution as i32))
+    Ok(Solution::Int(solution as i64))
 }
 
 pub fn solve(
@@ -1,81 +1,527 @@
-mod helpers;
-mod impls;
+use std::io::BufRead;
+use std::path::PathBuf;
 
-use clap::Parser;
-
-type AocResult<T> = Result<T, anyhow::Error>;
+use aoc_rust_2023::config::{self, OutputFormat};
+use aoc_rust_2023::impls::problem_2::{self, bags};
+use aoc_rust_2023::impls::problem_3::{self, render};
+use aoc_rust_2023::impls::problem_4::{self, graph};
+use aoc_rust_2023::impls::problem_5::{self, validate};
+use aoc_rust_2023::runner::{self, Source};
+use aoc_rust_2023::{batch, helpers, impls, memory, tui, watch, Part, SOLVED_YEAR};
+use clap::{Parser, Subcommand};
 
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(long, required = true)]
+    problem: Option<u8>,
+    #[arg(long, required = true)]
+    part: Option<u8>,
+    /// Report allocations and peak memory use for the parse step and the whole run.
+    /// Needs the count-alloc feature
     #[arg(long)]
-    problem: u8,
-    #[arg(long)]
-    part: u8,
+    memory: bool,
+    /// Abort with an error instead of using more than this much memory, e.g. 512M or 2G.
+    /// Needs the count-alloc feature
+    #[arg(long, global = true, value_parser = memory::parse_size)]
+    max_memory: Option<usize>,
+    #[command(flatten)]
+    overrides: config::Overrides,
 }
 
-pub enum Part {
-    One,
-    Two,
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Re-run a problem whenever its input, example or expected answer files change
+    Watch {
+        #[arg(long, visible_alias = "day")]
+        problem: u8,
+        /// Only run this part. Both parts are run if omitted.
+        #[arg(long)]
+        part: Option<u8>,
+    },
+    /// Browse every solved problem with its answers, status and timings
+    Tui,
+    /// Run every solved problem and part, checking the answers
+    All {
+        /// Run against the examples instead of the inputs
+        #[arg(long, conflicts_with = "all_profiles")]
+        examples: bool,
+        /// Run against the default inputs and every profile in aoc.toml
+        #[arg(long)]
+        all_profiles: bool,
+        /// Number of problems to solve at once
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+    /// Time every solved problem and part on its input
+    Bench {
+        /// Only benchmark this problem
+        #[arg(long)]
+        problem: Option<u8>,
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+        /// Number of benchmarks to run at once. Keep this at 1 for stable timings.
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+    /// Check problem 2 bags against the games, reading one bag per line like
+    /// red=12,green=13,blue=14. A bag holds none of the colors it doesn't list
+    Bags {
+        /// Read bags from this file instead of stdin
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Only consider these game IDs, e.g. 1,4,9
+        #[arg(long, value_delimiter = ',')]
+        games: Vec<u32>,
+        /// Print the smallest bags that make every considered game possible
+        /// (or at least --at-least of them) instead of reading any bags
+        #[arg(long)]
+        pareto: bool,
+        #[arg(long, requires = "pareto")]
+        at_least: Option<usize>,
+    },
+    /// Draw the problem 3 schematic with part numbers, rejected numbers, gears
+    /// and other symbols coloured
+    Schematic {
+        /// Write an .svg or .png image here instead of printing to the terminal
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Only draw the cells around ROW,COL (counting from 0)
+        #[arg(long, value_parser = parse_position)]
+        crop: Option<(usize, usize)>,
+        /// How many cells either side of --crop to draw
+        #[arg(long, default_value_t = 10, requires = "crop")]
+        radius: usize,
+        /// Draw the example instead of the input
+        #[arg(long)]
+        example: bool,
+    },
+    /// Export which problem 4 scratchcards won copies of which, as a DOT
+    /// graph or as JSON with --format json
+    Cascade {
+        /// Write a .dot or .json file here instead of printing
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Use the example instead of the input
+        #[arg(long)]
+        example: bool,
+    },
+    /// Look up problem 5 seeds and locations through the whole almanac at once
+    Almanac {
+        /// Print the location these seeds end up at, e.g. 79,14
+        #[arg(long, value_delimiter = ',')]
+        map_seed: Vec<u64>,
+        /// Print every seed that ends up at these locations
+        #[arg(long, value_delimiter = ',')]
+        unmap_location: Vec<u64>,
+        /// Check the maps for overlapping, empty, overflowing or colliding
+        /// ranges and unreachable categories, failing if any are found
+        #[arg(long)]
+        validate: bool,
+        /// Use the example instead of the input
+        #[arg(long)]
+        example: bool,
+    },
 }
 
-pub enum Solution {
-    Str(String),
-    Int(i32),
+fn run_watch(problem: u8, part: Option<u8>) -> Result<(), u8> {
+    if !impls::SOLVED_PROBLEMS.contains(&problem) {
+        return Err(255);
+    }
+    let parts = match part.map(Part::try_from) {
+        None => vec![Part::One, Part::Two],
+        Some(Ok(part)) => vec![part],
+        Some(Err(e)) => {
+            println!("Error!: {e}");
+            return Err(1);
+        }
+    };
+    match watch::watch(problem, parts) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            println!("Watching stopped: {e}");
+            Err(2)
+        }
+    }
 }
 
-impl std::fmt::Display for Solution {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Str(s) => f.write_str(s),
-            Self::Int(i) => f.write_str(&i.to_string()),
+fn run_all(examples: bool, all_profiles: bool, jobs: usize) -> Result<(), u8> {
+    let source = if examples {
+        Source::Example
+    } else {
+        Source::Input
+    };
+    let config = config::get();
+    let profiles = if all_profiles {
+        std::iter::once(None)
+            .chain(config.profile_names().map(|name| Some(name.to_string())))
+            .collect()
+    } else {
+        vec![config.profile.clone()]
+    };
+    match batch::run_all(&profiles, source, jobs) {
+        Ok(0) => Ok(()),
+        Ok(failures) => {
+            println!("{failures} runs failed or gave the wrong answer");
+            Err(2)
+        }
+        Err(e) => {
+            println!("An error occurred running all problems: {e}");
+            Err(2)
         }
     }
 }
 
-impl TryFrom<u8> for Part {
-    type Error = String;
+fn run_bench(problem: Option<u8>, iterations: u32, jobs: usize) -> Result<(), u8> {
+    let problems = match problem {
+        Some(problem) if !impls::SOLVED_PROBLEMS.contains(&problem) => return Err(255),
+        Some(problem) => vec![problem],
+        None => impls::SOLVED_PROBLEMS.to_vec(),
+    };
+    batch::bench_all(&problems, iterations, jobs);
+    Ok(())
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(Part::One),
-            2 => Ok(Part::Two),
-            _ => Err(format!("{value} is not a valid part. It must be 1 or 2.")),
+/// Reads the problem 2 games and either checks each bag from `file` (or
+/// stdin) against them, or prints the minimal bags.
+fn query_bags(
+    file: Option<PathBuf>,
+    game_ids: &[u32],
+    pareto: bool,
+    at_least: Option<usize>,
+) -> aoc_rust_2023::AocResult<()> {
+    let params = config::get().params(2);
+    let lines = helpers::open_file(&helpers::data_file_path(2))?;
+    let mut games = problem_2::parse_games(lines, &params)?;
+    if !game_ids.is_empty() {
+        games.retain(|game| game_ids.contains(&game.id));
+    }
+
+    if pareto {
+        let at_least = at_least.unwrap_or(games.len());
+        println!(
+            "Minimal bags making at least {at_least} of {} games possible:",
+            games.len()
+        );
+        for bag in bags::minimal_bags(&games, at_least) {
+            println!("{}", bags::format_bag(&bag));
+        }
+        return Ok(());
+    }
+
+    let input: Box<dyn BufRead> = match file {
+        Some(path) => Box::new(helpers::open_input(&path)?),
+        None => Box::new(std::io::stdin().lock()),
+    };
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bag = problem_2::parse_bag_str(line)?;
+        let report = bags::check_bag(&games, &bag);
+        let failing = report
+            .failing_colors
+            .iter()
+            .map(|(color, games)| format!("{color} ({games} games)"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match config::get().format {
+            OutputFormat::Text => println!(
+                "{}: sum {}, possible {:?}, too few {}",
+                bags::format_bag(&bag),
+                report.id_sum,
+                report.possible,
+                if failing.is_empty() { "none" } else { &failing }
+            ),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "bag": bag,
+                    "possible": report.possible,
+                    "id_sum": report.id_sum,
+                    "failing_colors": report.failing_colors,
+                })
+            ),
         }
     }
+    Ok(())
+}
+
+fn parse_position(value: &str) -> aoc_rust_2023::AocResult<(usize, usize)> {
+    let (row, col) = value
+        .split_once(',')
+        .ok_or(anyhow::anyhow!("Position={value} is not row,col"))?;
+    Ok((row.trim().parse()?, col.trim().parse()?))
+}
+
+fn draw_schematic(
+    output: Option<PathBuf>,
+    crop: Option<(usize, usize)>,
+    radius: usize,
+    example: bool,
+) -> aoc_rust_2023::AocResult<()> {
+    let source = if example {
+        Source::Example
+    } else {
+        Source::Input
+    };
+    let lines = helpers::open_file(&source.path(3, Part::Two))?;
+    let board = problem_3::Board::build_from_lines(lines)?;
+    let rule = problem_3::GearRule::from_params(&config::get().params(3))?;
+    let styles = render::styles(&board, &rule);
+    let view = match crop {
+        Some(position) => render::View::around(&board, position, radius)?,
+        None => render::View::whole(&board),
+    };
+    let Some(path) = output else {
+        print!("{}", render::to_ansi(&board, &styles, &view));
+        return Ok(());
+    };
+    let image = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => render::to_svg(&board, &styles, &view).into_bytes(),
+        Some("png") => render::to_png(&styles, &view)?,
+        _ => anyhow::bail!("Output={} must end in .svg or .png", path.display()),
+    };
+    std::fs::write(&path, image)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+/// Writes the problem 4 copy cascade, picking DOT or JSON from the output's
+/// extension, or from `--format` when printing.
+fn export_cascade(output: Option<PathBuf>, example: bool) -> aoc_rust_2023::AocResult<()> {
+    let source = if example {
+        Source::Example
+    } else {
+        Source::Input
+    };
+    let lines = helpers::open_file(&source.path(4, Part::Two))?;
+    let game = problem_4::ScratchcardGame::from_lines(lines)?;
+    let Some(path) = output else {
+        match config::get().format {
+            OutputFormat::Text => print!("{}", graph::to_dot(&game)),
+            OutputFormat::Json => println!("{}", graph::to_json(&game)),
+        }
+        return Ok(());
+    };
+    let exported = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("dot" | "gv") => graph::to_dot(&game),
+        Some("json") => serde_json::to_string_pretty(&graph::to_json(&game))?,
+        _ => anyhow::bail!("Output={} must end in .dot or .json", path.display()),
+    };
+    std::fs::write(&path, exported)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+/// Validates the problem 5 maps if asked, then answers seed and location
+/// lookups with the composed maps.
+fn query_almanac(
+    map_seed: &[u64],
+    unmap_location: &[u64],
+    validate: bool,
+    example: bool,
+) -> aoc_rust_2023::AocResult<()> {
+    let source = if example {
+        Source::Example
+    } else {
+        Source::Input
+    };
+    let lines = helpers::open_file(&source.path(5, Part::One))?;
+    let (_, maps) = problem_5::parse_almanac(lines)?;
+    let json = config::get().format == OutputFormat::Json;
+    if validate {
+        let issues = validate::validate(&maps);
+        for issue in &issues {
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({"issue": issue.kind(), "message": issue.to_string()})
+                );
+            } else {
+                println!("{issue}");
+            }
+        }
+        if !issues.is_empty() {
+            anyhow::bail!("Found {} issues in the almanac", issues.len());
+        }
+        if !json {
+            println!("The almanac is consistent");
+        }
+    }
+    if map_seed.is_empty() && unmap_location.is_empty() {
+        return Ok(());
+    }
+    let composite = maps.composite(problem_5::SEED, problem_5::LOCATION)?;
+    for &seed in map_seed {
+        let location = composite.apply(seed);
+        if json {
+            println!(
+                "{}",
+                serde_json::json!({"seed": seed, "location": location})
+            );
+        } else {
+            println!("Seed {seed} -> location {location}");
+        }
+    }
+    for &location in unmap_location {
+        let seeds = composite.preimage(location);
+        if json {
+            println!(
+                "{}",
+                serde_json::json!({"location": location, "seeds": seeds})
+            );
+        } else {
+            println!("Location {location} <- seeds {seeds:?}");
+        }
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), u8> {
     let args = Args::parse();
-    println!("Starting AOC 2023 with args: {args:?}");
-    let part = match Part::try_from(args.part) {
+    match config::Config::load(args.overrides.clone()) {
+        Ok(config) => config::init(config),
+        Err(e) => {
+            println!("Failed to load config: {e}");
+            return Err(3);
+        }
+    }
+    let config = config::get();
+    if config.format == OutputFormat::Text {
+        println!("Starting AOC {} with args: {args:?}", config.year);
+    }
+    if (args.memory || args.max_memory.is_some()) && !memory::ENABLED {
+        println!(
+            "--memory and --max-memory need allocations counted, build with --features count-alloc"
+        );
+        return Err(255);
+    }
+    if let Some(Command::All { jobs, .. } | Command::Bench { jobs, .. }) = &args.command {
+        if args.max_memory.is_some() && *jobs > 1 {
+            // The cap is on the whole process, so it couldn't tell which of
+            // the parallel solvers went over it.
+            println!("--max-memory needs --jobs 1, since it caps the whole process");
+            return Err(255);
+        }
+    }
+    memory::set_max_bytes(args.max_memory);
+    if config.year != SOLVED_YEAR {
+        println!(
+            "There are no solutions for {}, only {SOLVED_YEAR}",
+            config.year
+        );
+        return Err(255);
+    }
+    match args.command {
+        Some(Command::Watch { problem, part }) => return run_watch(problem, part),
+        Some(Command::Tui) => {
+            return tui::tui().map_err(|e| {
+                println!("An error occurred in the TUI: {e}");
+                2
+            })
+        }
+        Some(Command::All {
+            examples,
+            all_profiles,
+            jobs,
+        }) => return run_all(examples, all_profiles, jobs),
+        Some(Command::Bench {
+            problem,
+            iterations,
+            jobs,
+        }) => return run_bench(problem, iterations, jobs),
+        Some(Command::Bags {
+            file,
+            games,
+            pareto,
+            at_least,
+        }) => {
+            return query_bags(file, &games, pareto, at_least).map_err(|e| {
+                println!("An error occurred checking bags: {e}");
+                2
+            })
+        }
+        Some(Command::Schematic {
+            output,
+            crop,
+            radius,
+            example,
+        }) => {
+            return draw_schematic(output, crop, radius, example).map_err(|e| {
+                println!("An error occurred drawing the schematic: {e}");
+                2
+            })
+        }
+        Some(Command::Cascade { output, example }) => {
+            return export_cascade(output, example).map_err(|e| {
+                println!("An error occurred exporting the cascade: {e}");
+                2
+            })
+        }
+        Some(Command::Almanac {
+            map_seed,
+            unmap_location,
+            validate,
+            example,
+        }) => {
+            return query_almanac(&map_seed, &unmap_location, validate, example).map_err(|e| {
+                println!("An error occurred querying the almanac: {e}");
+                2
+            })
+        }
+        None => (),
+    }
+    // Both are required by clap when no subcommand is given.
+    let (problem, part_num) = (args.problem.unwrap(), args.part.unwrap());
+    let part = match Part::try_from(part_num) {
         Ok(part) => part,
         Err(e) => {
             println!("Error!: {e}");
             return Err(1);
         }
     };
-    let lines = match helpers::open_data_file(args.problem) {
-        Ok(lines) => lines,
+    if !impls::SOLVED_PROBLEMS.contains(&problem) {
+        return Err(255);
+    }
+    let run = match runner::run_file(problem, part, &helpers::data_file_path(problem)) {
+        Ok(run) => run,
         Err(e) => {
             println!("Failed to open file: {e}");
             return Err(254);
         }
     };
-    let result = match args.problem {
-        1 => impls::problem_1::solve(part, lines),
-        2 => impls::problem_2::solve(part, lines),
-        3 => impls::problem_3::solve(part, lines),
-        4 => impls::problem_4::solve(part, lines),
-        5 => impls::problem_5::solve(part, lines),
-        6 => impls::problem_6::solve(part, lines),
-        _ => return Err(255),
-    };
-    match result {
+    if args.memory {
+        for (phase, usage) in &run.memory {
+            println!("Memory ({phase}): {usage}");
+        }
+    }
+    if config.format == OutputFormat::Json {
+        let answer = run
+            .answer
+            .map(|solution| solution.to_string())
+            .map_err(|e| e.to_string());
+        let expected = helpers::read_answer_store()
+            .map(|answer_store| answer_store.get(&(problem, part)).cloned());
+        let status = batch::check_answer(&answer, expected);
+        batch::print_result(
+            config.profile.as_deref(),
+            problem,
+            part,
+            Source::Input,
+            &answer,
+            &status,
+            run.elapsed,
+        );
+        return answer.map(|_| ()).map_err(|_| 2);
+    }
+    match run.answer {
         Ok(solution) => {
-            println!(
-                "Problem: {}, Part{}, solution: {solution}",
-                args.problem, args.part
-            );
+            println!("Problem: {problem}, Part{part_num}, solution: {solution}");
             Ok(())
         }
         Err(e) => {
@@ -0,0 +1,261 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{AocResult, Part};
+
+/// Wraps the system allocator, counting every allocation so runs can report how
+/// much memory they used. The counters are process wide, so usage is only
+/// attributed correctly while one solver runs at a time.
+///
+/// It's only installed with the `count-alloc` feature, so normal builds don't
+/// pay for the counting. Without it every `Usage` is zero.
+pub struct CountingAllocator;
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Whether allocations are being counted, i.e. `--memory` and `--max-memory`
+/// can work.
+pub const ENABLED: bool = cfg!(feature = "count-alloc");
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+/// Zero means there is no cap.
+static MAX_BYTES_IN_USE: AtomicUsize = AtomicUsize::new(0);
+
+static PHASES: Mutex<Vec<(&'static str, Usage)>> = Mutex::new(Vec::new());
+/// Phases are only kept while `measure_run` is running, since nothing else
+/// would ever take them.
+static RECORDING_PHASES: AtomicBool = AtomicBool::new(false);
+/// Set once the cap has been hit, so the allocations made while exiting
+/// don't trip it again.
+static CAP_EXCEEDED: AtomicBool = AtomicBool::new(false);
+
+/// The exit code used when a solver goes over `--max-memory`.
+pub const CAP_EXCEEDED_EXIT_CODE: i32 = 4;
+
+thread_local! {
+    /// The problem and part being solved on this thread, to name in the error
+    /// if it goes over the cap.
+    static SOLVING: Cell<Option<(u8, Part)>> = const { Cell::new(None) };
+}
+
+impl CountingAllocator {
+    /// Exits if allocating `size` more bytes would go over the cap. This runs
+    /// before allocating so the cap is never actually exceeded. An allocator
+    /// can't fail a single run without unwinding, which it must not do, so the
+    /// whole process stops, naming the solver that went over when it can.
+    fn check_cap(&self, size: usize) {
+        let max = MAX_BYTES_IN_USE.load(Ordering::Relaxed);
+        if max == 0 || CAP_EXCEEDED.load(Ordering::Relaxed) {
+            return;
+        }
+        let in_use = BYTES_IN_USE.load(Ordering::Relaxed) + size;
+        if in_use <= max || CAP_EXCEEDED.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        // Allocating here would recurse, so write the message straight to
+        // stderr with no formatting buffers involved.
+        let mut stderr = std::io::stderr();
+        let _ = match SOLVING.try_with(Cell::get).ok().flatten() {
+            Some((problem, part)) => write!(stderr, "Problem {problem} part {part}"),
+            None => write!(stderr, "The runner"),
+        };
+        let _ = writeln!(
+            stderr,
+            " went over the --max-memory limit of {max} bytes: allocating {size} bytes \
+             would put {in_use} bytes in use"
+        );
+        std::process::exit(CAP_EXCEEDED_EXIT_CODE);
+    }
+
+    /// Counts an allocation once it has succeeded.
+    fn record_alloc(&self, size: usize) {
+        let in_use = BYTES_IN_USE.fetch_add(size, Ordering::Relaxed) + size;
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        PEAK_BYTES_IN_USE.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        BYTES_IN_USE.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.check_cap(layout.size());
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.check_cap(layout.size());
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let growth = new_size.saturating_sub(layout.size());
+        self.check_cap(growth);
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        // A failed realloc leaves the old block as it was.
+        if !new_ptr.is_null() {
+            // Count a growing realloc as a fresh allocation of the difference.
+            if growth > 0 {
+                self.record_alloc(growth);
+            } else {
+                self.record_dealloc(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    /// Highest number of bytes in use at once, above what was in use when
+    /// measuring started.
+    pub peak_bytes: usize,
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes_allocated),
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+/// Runs `f` and reports the memory it used. Measurements can be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_allocated = BYTES_ALLOCATED.load(Ordering::Relaxed);
+    let in_use = BYTES_IN_USE.load(Ordering::Relaxed);
+    let outer_peak = PEAK_BYTES_IN_USE.swap(in_use, Ordering::Relaxed);
+
+    let result = f();
+
+    let peak = PEAK_BYTES_IN_USE.fetch_max(outer_peak, Ordering::Relaxed);
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed) - bytes_allocated,
+        peak_bytes: peak.saturating_sub(in_use),
+    };
+    (result, usage)
+}
+
+/// Measures `f` like `measure`, and records the usage under `phase` so the
+/// runner can report it once the solver is done. Solvers use this to split
+/// out their parse step. Outside `measure_run` it just runs `f`.
+pub fn track<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    if !RECORDING_PHASES.load(Ordering::Relaxed) {
+        return f();
+    }
+    let (result, usage) = measure(f);
+    PHASES.lock().unwrap().push((phase, usage));
+    result
+}
+
+/// Measures a whole run, returning the usage of every phase it tracked
+/// followed by its `total`. Only one run can be measured at a time.
+pub fn measure_run<T>(f: impl FnOnce() -> T) -> (T, Vec<(&'static str, Usage)>) {
+    PHASES.lock().unwrap().clear();
+    RECORDING_PHASES.store(true, Ordering::Relaxed);
+    let (result, usage) = measure(f);
+    RECORDING_PHASES.store(false, Ordering::Relaxed);
+    let mut phases = std::mem::take(&mut *PHASES.lock().unwrap());
+    phases.push(("total", usage));
+    (result, phases)
+}
+
+/// Runs `f`, which solves `problem` and `part` on this thread, so going over
+/// the cap can say which solver did.
+pub fn solving<T>(problem: u8, part: Part, f: impl FnOnce() -> T) -> T {
+    let outer = SOLVING.replace(Some((problem, part)));
+    let result = f();
+    SOLVING.set(outer);
+    result
+}
+
+pub fn set_max_bytes(max_bytes: Option<usize>) {
+    MAX_BYTES_IN_USE.store(max_bytes.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Parses sizes like `512`, `64K`, `100M` or `2G` (powers of 1024).
+pub fn parse_size(value: &str) -> AocResult<usize> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&value[..value.len() - 1], 1 << 10),
+        Some('M') => (&value[..value.len() - 1], 1 << 20),
+        Some('G') => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    let size: usize = digits
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Size={value} is not a number of bytes, e.g. 512M"))?;
+    size.checked_mul(multiplier)
+        .ok_or(anyhow::anyhow!("Size={value} is too large"))
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_size;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("64k").unwrap(), 64 * 1024);
+        assert_eq!(parse_size("100M").unwrap(), 100 * 1024 * 1024);
+        assert_eq!(parse_size("2G").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert!(parse_size("lots").is_err());
+    }
+
+    #[cfg(feature = "count-alloc")]
+    #[test]
+    fn test_measure_counts_allocations() {
+        let (buffer, usage) = super::measure(|| vec![0u8; 1 << 20]);
+        assert!(usage.allocations >= 1);
+        assert!(usage.bytes_allocated >= 1 << 20);
+        assert!(usage.peak_bytes >= 1 << 20);
+        drop(buffer);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Params};
+use crate::memory::{self, Usage};
+use crate::{helpers, impls, AocResult, Part, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    Input,
+    Example,
+}
+
+impl Source {
+    pub fn path(self, problem: u8, part: Part) -> PathBuf {
+        match self {
+            Self::Input => helpers::data_file_path(problem),
+            Self::Example => helpers::example_file_path(problem, part),
+        }
+    }
+
+    /// Inputs are checked against the answer store, examples against their
+    /// `.answer` files.
+    pub fn expected_answer(
+        self,
+        answer_store: &HashMap<(u8, Part), String>,
+        problem: u8,
+        part: Part,
+    ) -> AocResult<Option<String>> {
+        match self {
+            Self::Input => Ok(answer_store.get(&(problem, part)).cloned()),
+            Self::Example => helpers::read_example_answer(problem, part),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input => f.pad("input"),
+            Self::Example => f.pad("example"),
+        }
+    }
+}
+
+pub struct RunResult {
+    pub answer: AocResult<Solution>,
+    pub elapsed: Duration,
+    /// Memory used by any phases the solver tracked, followed by the whole run.
+    /// Empty when the run wasn't measured.
+    pub memory: Vec<(&'static str, Usage)>,
+}
+
+pub struct BenchResult {
+    pub answer: AocResult<Solution>,
+    pub iterations: u32,
+    pub min: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+/// Runs a single part against the given file, using the problem's configured
+/// parameters and timeout. Only failing to open the file is an error here; a
+/// failing solver is reported through `RunResult::answer`.
+///
+/// Memory is counted process wide, so this must not run alongside other
+/// solvers; use `run_file_unmeasured` for those.
+pub fn run_file(problem: u8, part: Part, path: &Path) -> AocResult<RunResult> {
+    run(problem, part, path, true)
+}
+
+/// `run_file` without measuring memory, for runs that share the process with
+/// other solvers and would only be credited with their allocations too.
+pub fn run_file_unmeasured(problem: u8, part: Part, path: &Path) -> AocResult<RunResult> {
+    run(problem, part, path, false)
+}
+
+fn run(problem: u8, part: Part, path: &Path, measure: bool) -> AocResult<RunResult> {
+    memory::solving(problem, part, || {
+        let input = helpers::open_input(path)?;
+        let params = config::get().params(problem);
+        let timeout = config::get().timeout;
+        let solve = || match timeout {
+            None => impls::solve(problem, part, input, &params),
+            Some(timeout) => solve_with_timeout(problem, part, input, params, timeout),
+        };
+        let start = Instant::now();
+        let (answer, memory) = if measure {
+            memory::measure_run(solve)
+        } else {
+            (solve(), Vec::new())
+        };
+        Ok(RunResult {
+            answer,
+            elapsed: start.elapsed(),
+            memory,
+        })
+    })
+}
+
+/// Solves on a separate thread so we can stop waiting for it. A solver that
+/// times out is left running in the background, since threads can't be killed.
+fn solve_with_timeout(
+    problem: u8,
+    part: Part,
+    input: impl BufRead + Send + 'static,
+    params: Params,
+    timeout: Duration,
+) -> AocResult<Solution> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let answer = memory::solving(problem, part, || {
+            impls::solve(problem, part, input, &params)
+        });
+        let _ = sender.send(answer);
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(answer) => answer,
+        Err(mpsc::RecvTimeoutError::Timeout) => anyhow::bail!("Timed out after {timeout:?}"),
+        Err(mpsc::RecvTimeoutError::Disconnected) => anyhow::bail!("The solver panicked"),
+    }
+}
+
+/// Runs a single part `iterations` times, stopping early if the solver fails.
+/// File reads are included in the timings, just like a normal run. Memory
+/// isn't measured, since benches run in parallel.
+pub fn bench_file(problem: u8, part: Part, path: &Path, iterations: u32) -> AocResult<BenchResult> {
+    let mut timings = Vec::with_capacity(iterations as usize);
+    let mut answer = Err(anyhow::anyhow!("No iterations were run"));
+    for _ in 0..iterations.max(1) {
+        let run = run_file_unmeasured(problem, part, path)?;
+        timings.push(run.elapsed);
+        let failed = run.answer.is_err();
+        answer = run.answer;
+        if failed {
+            break;
+        }
+    }
+    let total: Duration = timings.iter().sum();
+    Ok(BenchResult {
+        answer,
+        iterations: timings.len() as u32,
+        min: timings.iter().min().copied().unwrap_or_default(),
+        mean: total / timings.len() as u32,
+        max: timings.iter().max().copied().unwrap_or_default(),
+    })
+}
+
+/// Calls `f` on every item using up to `jobs` threads. Results are handed to
+/// `on_result` in the same order as `items`, each as soon as it and everything
+/// before it has finished, so output stays deterministic however many jobs run.
+pub fn run_ordered<I: Sync, T: Send>(
+    items: &[I],
+    jobs: usize,
+    f: impl Fn(&I) -> T + Sync,
+    mut on_result: impl FnMut(&I, T),
+) {
+    let next_item = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next_item, f) = (&next_item, &f);
+            scope.spawn(move || loop {
+                let idx = next_item.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(idx) else {
+                    break;
+                };
+                if sender.send((idx, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<T>> = items.iter().map(|_| None).collect();
+        let mut next_to_report = 0;
+        for (idx, result) in receiver {
+            finished[idx] = Some(result);
+            while let Some(result) = finished.get_mut(next_to_report).and_then(Option::take) {
+                on_result(&items[next_to_report], result);
+                next_to_report += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_ordered;
+
+    #[test]
+    fn test_run_ordered_keeps_item_order() {
+        let items: Vec<u64> = (0..32).collect();
+        let mut seen = Vec::new();
+        run_ordered(
+            &items,
+            4,
+            |&item| {
+                // Make early items finish last.
+                std::thread::sleep(std::time::Duration::from_millis(32 - item));
+                item * 2
+            },
+            |&item, doubled| seen.push((item, doubled)),
+        );
+        let expected: Vec<(u64, u64)> = items.iter().map(|&item| (item, item * 2)).collect();
+        assert_eq!(seen, expected);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::runner::{self, Source};
+use crate::{helpers, impls, AocResult, Part};
+
+const BENCH_ITERATIONS: u32 = 10;
+const HELP: &str =
+    "up/down: select  r: run input  e: run example  b: benchmark  x: explain  q: quit";
+
+struct LastRun {
+    source: Source,
+    answer: Result<String, String>,
+    elapsed: Duration,
+}
+
+struct Entry {
+    problem: u8,
+    part: Part,
+    last_run: Option<LastRun>,
+}
+
+impl Entry {
+    fn path(&self, source: Source) -> PathBuf {
+        source.path(self.problem, self.part)
+    }
+}
+
+enum Status {
+    NotRun,
+    Correct,
+    Wrong(String),
+    Unverified,
+    Failed,
+}
+
+impl Status {
+    fn label(&self) -> String {
+        match self {
+            Self::NotRun => "-".to_string(),
+            Self::Correct => "correct".to_string(),
+            Self::Wrong(expected) => format!("WRONG (want {expected})"),
+            Self::Unverified => "unverified".to_string(),
+            Self::Failed => "error".to_string(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::NotRun => Color::DarkGray,
+            Self::Correct => Color::Green,
+            Self::Wrong(_) | Self::Failed => Color::Red,
+            Self::Unverified => Color::Yellow,
+        }
+    }
+}
+
+struct App {
+    entries: Vec<Entry>,
+    answer_store: HashMap<(u8, Part), String>,
+    table_state: TableState,
+    details: String,
+}
+
+impl App {
+    fn new() -> AocResult<Self> {
+        let mut entries = Vec::new();
+        for problem in impls::SOLVED_PROBLEMS {
+            for part in [Part::One, Part::Two] {
+                entries.push(Entry {
+                    problem,
+                    part,
+                    last_run: None,
+                });
+            }
+        }
+        Ok(Self {
+            entries,
+            answer_store: helpers::read_answer_store()?,
+            table_state: TableState::default().with_selected(0),
+            details: HELP.to_string(),
+        })
+    }
+
+    fn selected(&self) -> usize {
+        self.table_state.selected().unwrap_or_default()
+    }
+
+    fn expected_answer(&self, entry: &Entry, source: Source) -> Option<String> {
+        source
+            .expected_answer(&self.answer_store, entry.problem, entry.part)
+            .ok()
+            .flatten()
+    }
+
+    fn status(&self, entry: &Entry) -> Status {
+        let Some(last_run) = &entry.last_run else {
+            return Status::NotRun;
+        };
+        let Ok(answer) = &last_run.answer else {
+            return Status::Failed;
+        };
+        match self.expected_answer(entry, last_run.source) {
+            Some(expected) if &expected == answer => Status::Correct,
+            Some(expected) => Status::Wrong(expected),
+            None => Status::Unverified,
+        }
+    }
+
+    /// Failures to even start the run, like an unreadable file, are shown in
+    /// the details rather than closing the dashboard.
+    fn run_selected(&mut self, source: Source) {
+        let selected = self.selected();
+        let entry = &mut self.entries[selected];
+        let path = entry.path(source);
+        if !path.exists() {
+            self.details = format!("No file at {}", path.display());
+            return;
+        }
+        let run = match runner::run_file(entry.problem, entry.part, &path) {
+            Ok(run) => run,
+            Err(e) => {
+                self.details = format!("Failed to run {}: {e}", path.display());
+                return;
+            }
+        };
+        entry.last_run = Some(LastRun {
+            source,
+            answer: run
+                .answer
+                .map(|solution| solution.to_string())
+                .map_err(|e| e.to_string()),
+            elapsed: run.elapsed,
+        });
+        self.explain_selected();
+    }
+
+    fn bench_selected(&mut self) {
+        let entry = &self.entries[self.selected()];
+        let path = entry.path(Source::Input);
+        if !path.exists() {
+            self.details = format!("No file at {}", path.display());
+            return;
+        }
+        let bench = match runner::bench_file(entry.problem, entry.part, &path, BENCH_ITERATIONS) {
+            Ok(bench) => bench,
+            Err(e) => {
+                self.details = format!("Failed to benchmark {}: {e}", path.display());
+                return;
+            }
+        };
+        let answer = match bench.answer {
+            Ok(solution) => solution.to_string(),
+            Err(e) => format!("error: {e}"),
+        };
+        self.details = format!(
+            "Problem {} part {}: {} iterations, min {:?}, mean {:?}, max {:?}\nanswer: {answer}",
+            entry.problem, entry.part, bench.iterations, bench.min, bench.mean, bench.max
+        );
+    }
+
+    fn explain_selected(&mut self) {
+        let entry = &self.entries[self.selected()];
+        let mut lines = vec![format!("Problem {} part {}", entry.problem, entry.part)];
+        for source in [Source::Input, Source::Example] {
+            let path = entry.path(source);
+            let exists = if path.exists() { "" } else { " (missing)" };
+            let expected = self
+                .expected_answer(entry, source)
+                .unwrap_or_else(|| "unknown".to_string());
+            lines.push(format!(
+                "{source}: {}{exists}, expected answer: {expected}",
+                path.display()
+            ));
+        }
+        if let Some(last_run) = &entry.last_run {
+            let answer = match &last_run.answer {
+                Ok(answer) => answer.clone(),
+                Err(e) => format!("error: {e}"),
+            };
+            lines.push(format!(
+                "last run on {}: {answer} in {:?}",
+                last_run.source, last_run.elapsed
+            ));
+        }
+        self.details = lines.join("\n");
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        let selected = self.selected();
+        let selected = if forward {
+            (selected + 1).min(self.entries.len() - 1)
+        } else {
+            selected.saturating_sub(1)
+        };
+        self.table_state.select(Some(selected));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, details_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(7)]).areas(frame.area());
+
+        let header = Row::new([
+            "Problem", "Part", "Input", "Example", "Answer", "Status", "Runtime",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows: Vec<Row> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let status = self.status(entry);
+                let (answer, runtime) = match &entry.last_run {
+                    Some(LastRun {
+                        answer, elapsed, ..
+                    }) => (answer.clone().unwrap_or_else(|e| e), format!("{elapsed:?}")),
+                    None => ("-".to_string(), "-".to_string()),
+                };
+                let exists = |path: PathBuf| if path.exists() { "yes" } else { "no" };
+                Row::new([
+                    Cell::from(entry.problem.to_string()),
+                    Cell::from(entry.part.to_string()),
+                    Cell::from(exists(entry.path(Source::Input))),
+                    Cell::from(exists(entry.path(Source::Example))),
+                    Cell::from(answer),
+                    Cell::from(status.label()).style(Style::default().fg(status.color())),
+                    Cell::from(runtime),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(5),
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Min(12),
+                Constraint::Length(24),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title("AOC 2023"));
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+
+        let details = Paragraph::new(self.details.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(HELP));
+        frame.render_widget(details, details_area);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> AocResult<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.move_selection(true),
+                KeyCode::Up | KeyCode::Char('k') => self.move_selection(false),
+                KeyCode::Char('r') => self.run_selected(Source::Input),
+                KeyCode::Char('e') => self.run_selected(Source::Example),
+                KeyCode::Char('b') => self.bench_selected(),
+                KeyCode::Char('x') => self.explain_selected(),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Shows every solved problem and part in a table that can run, benchmark and
+/// explain the selected entry.
+pub fn tui() -> AocResult<()> {
+    let mut app = App::new()?;
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
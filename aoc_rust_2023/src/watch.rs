@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::runner::{self, Source};
+use crate::{helpers, AocResult, Part};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Watcher {
+    problem: u8,
+    parts: Vec<Part>,
+    modified_times: HashMap<PathBuf, Option<SystemTime>>,
+    previous_answers: HashMap<(Source, Part), String>,
+}
+
+impl Watcher {
+    fn new(problem: u8, parts: Vec<Part>) -> Self {
+        Self {
+            problem,
+            parts,
+            modified_times: HashMap::new(),
+            previous_answers: HashMap::new(),
+        }
+    }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![helpers::data_file_path(self.problem)];
+        for &part in &self.parts {
+            paths.push(helpers::example_file_path(self.problem, part));
+            paths.push(helpers::example_answer_path(self.problem, part));
+        }
+        paths
+    }
+
+    /// Returns true if any watched file was created, deleted or modified since
+    /// the last poll. The first poll always reports a change.
+    fn poll_for_changes(&mut self) -> bool {
+        let mut changed = false;
+        let mut modified_times = HashMap::new();
+        for path in self.watched_paths() {
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            if self.modified_times.get(&path) != Some(&modified) {
+                changed = true;
+            }
+            modified_times.insert(path, modified);
+        }
+        self.modified_times = modified_times;
+        changed
+    }
+
+    fn run_once(&mut self, run_number: usize) -> AocResult<()> {
+        println!("== Problem {} (run #{run_number}) ==", self.problem);
+        for part in self.parts.clone() {
+            for source in [Source::Input, Source::Example] {
+                let path = source.path(self.problem, part);
+                if !path.exists() {
+                    println!("{source:<7} part {part}: no file at {}", path.display());
+                    continue;
+                }
+                let answer = match runner::run_file(self.problem, part, &path)?.answer {
+                    Ok(solution) => solution.to_string(),
+                    Err(e) => format!("error: {e}"),
+                };
+                let mut report = format!("{source:<7} part {part}: {answer}");
+                if source == Source::Example {
+                    match helpers::read_example_answer(self.problem, part)? {
+                        Some(expected) if expected == answer => report.push_str(" [ok]"),
+                        Some(expected) => {
+                            report.push_str(&format!(" [MISMATCH, expected {expected}]"))
+                        }
+                        None => report.push_str(" [no expected answer]"),
+                    }
+                }
+                match self.previous_answers.insert((source, part), answer.clone()) {
+                    Some(previous) if previous == answer => report.push_str(" (unchanged)"),
+                    Some(previous) => report.push_str(&format!(" (was {previous})")),
+                    None => (),
+                }
+                println!("{report}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Re-runs the given parts of a problem against its input and example files
+/// every time one of them (or an expected example answer) is saved.
+pub fn watch(problem: u8, parts: Vec<Part>) -> AocResult<()> {
+    let mut watcher = Watcher::new(problem, parts);
+    println!("Watching: {:?}", watcher.watched_paths());
+    let mut run_number = 0;
+    loop {
+        if watcher.poll_for_changes() {
+            run_number += 1;
+            watcher.run_once(run_number)?;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}